@@ -1,11 +1,12 @@
 use cosmwasm_schema::write_api;
 
-use comdex_nft_marketplace::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use comdex_nft_marketplace::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SudoMsg};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
         query: QueryMsg,
+        sudo: SudoMsg,
     }
 }
@@ -1,50 +1,149 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, StdResult};
+use cosmwasm_std::{Deps, DepsMut, Env, MessageInfo, StdResult};
 
-// use cw2::set_contract_version;
+use cw2::set_contract_version;
 
 use crate::error::ContractError;
+use crate::helpers::{fee_distribution_validate, map_validate};
 use crate::msg::{
-    AskHookMsg, BidHookMsg,MintMsg, ExecuteMsg, HookAction, InstantiateMsg, QueryMsg, SaleHookMsg,
+    AskHookMsg, BidHookMsg, CollectionBidHookMsg, Cw2981QueryMsg, ExecuteMsg, HookAction,
+    InstantiateMsg, MintMsg, MintVoucher, PayoutBreakdown, ReceiveMsg, RoyaltiesInfoResponse,
+    RoyaltyPayout, RoyaltyShareMsg, SaleHookMsg, SignedAsk,
 };
 use crate::state::{
-    ask_key, asks, bid_key,TOKENS, bids,CONFIG ,Ask, Bid, State,Order, SaleType,TokenInfo ,SudoParams, TokenId, ASK_HOOKS,
-    BID_HOOKS, SALE_HOOKS, SUDO_PARAMS,
+    ask_key, asks, bid_key, bids, collection_bid_key, collection_bids, tokens, Ask, AskKey,
+    AssetParams, AssetType, Bid, CollectionBid, DutchAuction, FeeDestination, ListingFee, Order,
+    PaymentAsset, RoyaltyShare, SaleType, State, SudoParams, TokenId, TokenInfo, ASK_HOOKS,
+    AUCTION_RESERVE_PRICES, BID_HOOKS, COLLECTION_BID_HOOKS, CONFIG, DENOM, FEE_DISTRIBUTION,
+    LISTING_FEES, PAYMENT_ASSETS, ROYALTIES, SALE_HOOKS, SIGNED_ORDER_NONCES, SUDO_PARAMS,
+    USED_VOUCHER_NONCES,
 };
 use cosmwasm_std::{
-    coin, to_binary, Addr, BankMsg, BlockInfo, Coin, Decimal, Empty, Event, StdError, Storage,
-    Timestamp, Uint128, WasmMsg,
+    coin, from_binary, to_binary, to_vec, Addr, Api, BankMsg, Binary, BlockInfo, CanonicalAddr,
+    Coin, Decimal, Empty, Event, Reply, StdError, Storage, Timestamp, Uint128, WasmMsg,
 };
-use cw721::{Cw721ExecuteMsg, OwnerOfResponse};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw721::Cw721ExecuteMsg;
 use cw721_base::helpers::Cw721Contract;
-use cw_utils::{may_pay, maybe_addr, must_pay, nonpayable};
-use sg1::fair_burn;
+use cw721_base::{MinterResponse, QueryMsg as Cw721QueryMsg};
+use cw_storage_plus::Bound;
+use cw_utils::{maybe_addr, must_pay, nonpayable, one_coin, Duration, Expiration};
+use ripemd::Ripemd160;
+use serde::Serialize;
 use sg721::RoyaltyInfoResponse;
 use sg721_base::msg::{CollectionInfoResponse, QueryMsg as Sg721QueryMsg};
-use sg_std::{Response, SubMsg};
+use sg_std::{create_fund_community_pool_msg, CosmosMsg, Response, SubMsg};
+use sha2::{Digest, Sha256};
 use std::cmp::Ordering;
 use std::marker::PhantomData;
 
-pub const NATIVE_DENOM: &str = "CMDX";
-
-/*
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:comdex-nft-marketplace";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
-*/
+
+// Limits for batch operations over stored asks and bids
+const DEFAULT_BATCH_LIMIT: u32 = 30;
+const MAX_BATCH_LIMIT: u32 = 100;
+
+// bps fees can not exceed 100%
+pub const MAX_FEE_BPS: u64 = 10000;
+
+// auctions can not be extended by more than a day per late bid
+pub const MAX_AUCTION_EXTENSION: u64 = 24 * 60 * 60;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
-    _msg: InstantiateMsg,
+    info: MessageInfo,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    Ok(Response::new())
-}
+    nonpayable(&info)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if msg.denom.is_empty() {
+        return Err(ContractError::InvalidDenom(msg.denom));
+    }
+    msg.ask_expiry.validate()?;
+    msg.bid_expiry.validate()?;
+    if msg.trading_fee_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidTradingFeeBps(msg.trading_fee_bps));
+    }
+    if msg.max_finders_fee_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidFindersFeeBps(msg.max_finders_fee_bps));
+    }
+    if msg.bid_removal_reward_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidBidRemovalRewardBps(
+            msg.bid_removal_reward_bps,
+        ));
+    }
+    msg.listing_fee_destination.validate(deps.api)?;
+    if msg.min_bid_increment_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidBidIncrementBps(
+            msg.min_bid_increment_bps,
+        ));
+    }
+    if msg.max_royalty_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidRoyaltyBps(msg.max_royalty_bps));
+    }
+    // stale bids are timed from their expiry timestamp, so heights can't be added to it
+    if !matches!(msg.stale_bid_duration, Duration::Time(_)) {
+        return Err(ContractError::InvalidStaleBidDuration {});
+    }
+    for extension in [msg.auction_extension_window, msg.auction_extension_duration] {
+        if extension > MAX_AUCTION_EXTENSION {
+            return Err(ContractError::InvalidAuctionExtension(extension));
+        }
+    }
+
+    let params = SudoParams {
+        trading_fee_percent: Decimal::from_ratio(msg.trading_fee_bps, MAX_FEE_BPS),
+        ask_expiry: msg.ask_expiry,
+        bid_expiry: msg.bid_expiry,
+        operators: map_validate(deps.api, &msg.operators)?,
+        max_finders_fee_percent: Decimal::from_ratio(msg.max_finders_fee_bps, MAX_FEE_BPS),
+        min_price: msg.min_price,
+        stale_bid_duration: msg.stale_bid_duration,
+        bid_removal_reward_percent: Decimal::from_ratio(msg.bid_removal_reward_bps, MAX_FEE_BPS),
+        listing_fee: msg.listing_fee,
+        auction_extension_window: msg.auction_extension_window,
+        auction_extension_duration: msg.auction_extension_duration,
+        min_bid_increment_percent: Decimal::from_ratio(msg.min_bid_increment_bps, MAX_FEE_BPS),
+        listing_fee_destination: msg.listing_fee_destination,
+        listing_fee_grace_period: msg.listing_fee_grace_period,
+        max_royalty_percent: Decimal::from_ratio(msg.max_royalty_bps, MAX_FEE_BPS),
+    };
+    SUDO_PARAMS.save(deps.storage, &params)?;
+    FEE_DISTRIBUTION.save(
+        deps.storage,
+        &fee_distribution_validate(deps.api, msg.fee_distribution)?,
+    )?;
+    DENOM.save(deps.storage, &msg.denom)?;
+    PAYMENT_ASSETS.save(
+        deps.storage,
+        &msg.denom,
+        &PaymentAsset {
+            asset_type: AssetType::Native,
+            enabled: true,
+        },
+    )?;
 
+    let config = State {
+        name: msg.name,
+        symbol: msg.symbol,
+        minter: deps.api.addr_validate(&msg.minter)?,
+        num_tokens: 0,
+    };
+    CONFIG.save(deps.storage, &config)?;
 
+    Ok(Response::new()
+        .add_attribute("action", "instantiate")
+        .add_attribute("contract_name", CONTRACT_NAME)
+        .add_attribute("contract_version", CONTRACT_VERSION)
+        .add_attribute("denom", msg.denom)
+        .add_attribute("sender", info.sender))
+}
 
 pub struct NFTinfo {
     sale_type: SaleType,
@@ -55,6 +154,29 @@ pub struct NFTinfo {
     reserve_for: Option<Addr>,
     finders_fee_bps: Option<u64>,
     expires: Timestamp,
+    reserve_price: Option<Coin>,
+    min_bid_increment_bps: Option<u64>,
+}
+
+pub struct ListInfo {
+    sale_type: SaleType,
+    price: Option<Coin>,
+    funds_recipient: Option<Addr>,
+    reserve_for: Option<Addr>,
+    finders_fee_bps: Option<u64>,
+    expires: Timestamp,
+}
+
+pub struct DutchAuctionInfo {
+    collection: Addr,
+    token_id: TokenId,
+    start_price: Coin,
+    end_price: Coin,
+    start_time: Timestamp,
+    end_time: Timestamp,
+    funds_recipient: Option<Addr>,
+    finders_fee_bps: Option<u64>,
+    expires: Timestamp,
 }
 
 pub struct BidInfo {
@@ -84,6 +206,8 @@ pub fn execute(
             reserve_for,
             finders_fee_bps,
             expires,
+            reserve_price,
+            min_bid_increment_bps,
         } => execute_set_ask(
             deps,
             env,
@@ -97,6 +221,34 @@ pub fn execute(
                 reserve_for: maybe_addr(api, reserve_for)?,
                 finders_fee_bps,
                 expires,
+                reserve_price,
+                min_bid_increment_bps,
+            },
+        ),
+        ExecuteMsg::SetDutchAuction {
+            collection,
+            token_id,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            funds_recipient,
+            finders_fee_bps,
+            expires,
+        } => execute_set_dutch_auction(
+            deps,
+            env,
+            info,
+            DutchAuctionInfo {
+                collection: api.addr_validate(&collection)?,
+                token_id,
+                start_price,
+                end_price,
+                start_time,
+                end_time,
+                funds_recipient: maybe_addr(api, funds_recipient)?,
+                finders_fee_bps,
+                expires,
             },
         ),
         ExecuteMsg::SetBid {
@@ -106,10 +258,253 @@ pub fn execute(
             finder,
             finders_fee_bps,
             sale_type,
-        } => execute_set_bid(
+        } => {
+            let payment = native_payment(deps.storage, &info)?;
+            execute_set_bid(
+                deps,
+                env,
+                info.sender,
+                payment,
+                sale_type,
+                BidInfo {
+                    collection: api.addr_validate(&collection)?,
+                    token_id,
+                    expires,
+                    finder: maybe_addr(api, finder)?,
+                    finders_fee_bps,
+                },
+                false,
+            )
+        }
+        ExecuteMsg::BuyNow {
+            collection,
+            token_id,
+            expires,
+            finder,
+            finders_fee_bps,
+        } => {
+            let payment = native_payment(deps.storage, &info)?;
+            execute_set_bid(
+                deps,
+                env,
+                info.sender,
+                payment,
+                SaleType::FixedPrice,
+                BidInfo {
+                    collection: api.addr_validate(&collection)?,
+                    token_id,
+                    expires,
+                    finder: maybe_addr(api, finder)?,
+                    finders_fee_bps,
+                },
+                true,
+            )
+        }
+        ExecuteMsg::AcceptBid {
+            collection,
+            token_id,
+            bidder,
+            finder,
+        } => execute_accept_bid(
+            deps,
+            env,
+            info,
+            api.addr_validate(&collection)?,
+            token_id,
+            api.addr_validate(&bidder)?,
+            maybe_addr(api, finder)?,
+        ),
+        ExecuteMsg::RemoveAsk {
+            collection,
+            token_id,
+        } => execute_remove_ask(deps, env, info, api.addr_validate(&collection)?, token_id),
+        ExecuteMsg::UpdateAskPrice {
+            collection,
+            token_id,
+            price,
+        } => execute_update_ask_price(
+            deps,
+            env,
+            info,
+            api.addr_validate(&collection)?,
+            token_id,
+            price,
+        ),
+        ExecuteMsg::SyncAsk {
+            collection,
+            token_id,
+        } => execute_sync_ask(deps, env, info, api.addr_validate(&collection)?, token_id),
+        ExecuteMsg::UpdateAskState {
+            collection,
+            token_id,
+            active,
+        } => execute_update_ask_state(
+            deps,
+            info,
+            api.addr_validate(&collection)?,
+            token_id,
+            active,
+        ),
+        ExecuteMsg::RemoveStaleAsk {
+            collection,
+            token_id,
+        } => execute_remove_stale_ask(deps, env, info, api.addr_validate(&collection)?, token_id),
+        ExecuteMsg::RemoveStaleAsks {
+            collection,
+            start_after,
+            limit,
+        } => execute_remove_stale_asks(
+            deps,
+            env,
+            info,
+            api.addr_validate(&collection)?,
+            start_after,
+            limit,
+        ),
+        ExecuteMsg::RemoveBid {
+            collection,
+            token_id,
+        } => execute_remove_bid(deps, info, api.addr_validate(&collection)?, token_id),
+        ExecuteMsg::RemoveStaleBid {
+            collection,
+            token_id,
+            bidder,
+        } => execute_remove_stale_bid(
+            deps,
+            env,
+            info,
+            api.addr_validate(&collection)?,
+            token_id,
+            api.addr_validate(&bidder)?,
+        ),
+        ExecuteMsg::Mint {
+            owner,
+            token_uri,
+            base_price,
+        } => handle_mint(
+            deps,
+            env,
+            info,
+            MintMsg {
+                owner,
+                token_uri,
+                price: base_price,
+            },
+        ),
+        ExecuteMsg::MintAndList {
+            token_uri,
+            base_price,
+            sale_type,
+            price,
+            funds_recipient,
+            reserve_for,
+            finders_fee_bps,
+            expires,
+        } => {
+            let mint_msg = MintMsg {
+                owner: info.sender.to_string(),
+                token_uri,
+                price: base_price,
+            };
+            execute_mint_and_list(
+                deps,
+                env,
+                info,
+                mint_msg,
+                ListInfo {
+                    sale_type,
+                    price,
+                    funds_recipient: maybe_addr(api, funds_recipient)?,
+                    reserve_for: maybe_addr(api, reserve_for)?,
+                    finders_fee_bps,
+                    expires,
+                },
+            )
+        }
+        ExecuteMsg::RedeemVoucher { voucher, signature } => {
+            execute_redeem_voucher(deps, env, info, voucher, signature)
+        }
+        ExecuteMsg::FillSignedAsk {
+            order,
+            signature,
+            finder,
+        } => execute_fill_signed_ask(deps, env, info, order, signature, maybe_addr(api, finder)?),
+        ExecuteMsg::CancelSignedOrders { nonces } => {
+            execute_cancel_signed_orders(deps, info, nonces)
+        }
+        ExecuteMsg::SettleAuction {
+            collection,
+            token_id,
+        } => execute_settle_auction(deps, env, info, api.addr_validate(&collection)?, token_id),
+        ExecuteMsg::SetCollectionBid {
+            collection,
+            finders_fee_bps,
+            expires,
+        } => {
+            let payment = native_payment(deps.storage, &info)?;
+            execute_set_collection_bid(
+                deps,
+                env,
+                info.sender,
+                payment,
+                api.addr_validate(&collection)?,
+                finders_fee_bps,
+                expires,
+            )
+        }
+        ExecuteMsg::RemoveCollectionBid { collection } => {
+            execute_remove_collection_bid(deps, info, api.addr_validate(&collection)?)
+        }
+        ExecuteMsg::AcceptCollectionBid {
+            collection,
+            token_id,
+            bidder,
+            finder,
+        } => execute_accept_collection_bid(
             deps,
             env,
             info,
+            api.addr_validate(&collection)?,
+            token_id,
+            api.addr_validate(&bidder)?,
+            maybe_addr(api, finder)?,
+        ),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::SetRoyalties {
+            collection,
+            royalties,
+        } => execute_set_royalties(deps, info, api.addr_validate(&collection)?, royalties),
+    }
+}
+
+/// Places bids paid in CW20 tokens, the sender is the token contract
+pub fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let api = deps.api;
+    let denom = info.sender.to_string();
+    if asset_validate(deps.storage, &denom)? != AssetType::Cw20 {
+        return Err(ContractError::InvalidDenom(denom));
+    }
+    let bidder = api.addr_validate(&wrapper.sender)?;
+    let payment = coin(wrapper.amount.u128(), denom);
+
+    match from_binary(&wrapper.msg)? {
+        ReceiveMsg::SetBid {
+            collection,
+            token_id,
+            expires,
+            sale_type,
+            finder,
+            finders_fee_bps,
+        } => execute_set_bid(
+            deps,
+            env,
+            bidder,
+            payment,
             sale_type,
             BidInfo {
                 collection: api.addr_validate(&collection)?,
@@ -120,7 +515,7 @@ pub fn execute(
             },
             false,
         ),
-        ExecuteMsg::BuyNow {
+        ReceiveMsg::BuyNow {
             collection,
             token_id,
             expires,
@@ -129,7 +524,8 @@ pub fn execute(
         } => execute_set_bid(
             deps,
             env,
-            info,
+            bidder,
+            payment,
             SaleType::FixedPrice,
             BidInfo {
                 collection: api.addr_validate(&collection)?,
@@ -140,60 +536,167 @@ pub fn execute(
             },
             true,
         ),
-        ExecuteMsg::AcceptBid {
+        ReceiveMsg::SetCollectionBid {
             collection,
-            token_id,
-            bidder,
-            finder,
-        } => execute_accept_bid(
+            finders_fee_bps,
+            expires,
+        } => execute_set_collection_bid(
             deps,
             env,
-            info,
+            bidder,
+            payment,
             api.addr_validate(&collection)?,
-            token_id,
-            api.addr_validate(&bidder)?,
-            maybe_addr(api, finder)?,
+            finders_fee_bps,
+            expires,
         ),
-
-        ExecuteMsg::Mint {
-            token_name,
-            asset_description,
-            owner,
-            token_URL,
-        } => ,
     }
 }
 
+/// Mints a new native token, only the minter can mint
 pub fn handle_mint(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     msg: MintMsg,
 ) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let config = query_config(deps.as_ref())?;
+    if info.sender != config.minter {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let owner = deps.api.addr_validate(&msg.owner)?;
+    let num_tokens = mint_token(deps.storage, owner, msg.token_uri, msg.price)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "mint")
+        .add_attribute("from", info.sender)
+        .add_attribute("owner", msg.owner)
+        .add_attribute("token_id", num_tokens.to_string()))
+}
 
-    let mut config = query_config(deps.as_ref())?;
-    
+/// Stores a new native token and bumps the token count, returning the new token id
+fn mint_token(
+    store: &mut dyn Storage,
+    owner: Addr,
+    token_uri: Option<String>,
+    base_price: Vec<Coin>,
+) -> StdResult<u64> {
+    let mut config = CONFIG.load(store)?;
     let num_tokens = config.num_tokens + 1;
 
     let token = TokenInfo {
-        owner: deps.api.addr_validate(&msg.owner)?,
-        token_uri: msg.token_uri,
-        base_price: msg.price,
+        owner,
+        token_uri,
+        base_price,
         token_id: num_tokens,
     };
-     
-    
-    TOKENS.save(deps.storage, num_tokens, &token)?;
+    tokens().save(store, num_tokens, &token)?;
 
-  
     config.num_tokens = num_tokens;
-    CONFIG.save(deps.storage, &config)?;
+    CONFIG.save(store, &config)?;
 
-    Ok(Response::new()
+    Ok(num_tokens)
+}
+
+/// Mints a token described by a creator-signed voucher to the buyer, who pays the voucher price.
+/// The signature covers `sha256(contract_address || voucher_json)`, so a voucher can only be
+/// redeemed on the marketplace it was signed for.
+pub fn execute_redeem_voucher(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    voucher: MintVoucher,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    if voucher.expires <= env.block.time {
+        return Err(ContractError::VoucherExpired {});
+    }
+
+    price_validate(deps.storage, &voucher.price)?;
+    only_native_price(deps.storage, &voucher.price)?;
+    let payment = must_pay(&info, &voucher.price.denom)?;
+    if payment != voucher.price.amount {
+        return Err(ContractError::InvalidPrice {});
+    }
+
+    verify_signature(
+        deps.api,
+        &env.contract.address,
+        &voucher,
+        &signature,
+        &voucher.creator_pubkey,
+    )?;
+
+    let nonce_key = (voucher.creator_pubkey.as_slice(), voucher.nonce);
+    if USED_VOUCHER_NONCES.has(deps.storage, nonce_key) {
+        return Err(ContractError::VoucherAlreadyRedeemed {});
+    }
+    USED_VOUCHER_NONCES.save(deps.storage, nonce_key, &true)?;
+
+    let creator = pubkey_to_addr(deps.api, &voucher.creator_pubkey)?;
+    let buyer = info.sender;
+    let token_id = mint_token(
+        deps.storage,
+        buyer.clone(),
+        voucher.token_uri.clone(),
+        vec![voucher.price.clone()],
+    )?;
+
+    let mut res = Response::new();
+    payout(
+        deps.as_ref(),
+        env.contract.address,
+        vec![],
+        voucher.price.clone(),
+        creator.clone(),
+        None,
+        None,
+        &mut res,
+    )?;
+
+    let event = Event::new("redeem-voucher")
+        .add_attribute("creator", creator)
+        .add_attribute("buyer", buyer.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("nonce", voucher.nonce.to_string())
+        .add_attribute("price", voucher.price.to_string());
+
+    Ok(res
         .add_attribute("action", "mint")
-        .add_attribute("from", info.sender)
-        .add_attribute("owner", msg.owner)
-        .add_attribute("token_id", num_tokens.to_string()))
+        .add_attribute("owner", buyer)
+        .add_attribute("token_id", token_id.to_string())
+        .add_event(event))
+}
+
+/// Verifies a secp256k1 signature over `sha256(contract_address || payload_json)`.
+/// Binding the contract address prevents replaying a signature on another marketplace.
+fn verify_signature<T: Serialize>(
+    api: &dyn Api,
+    contract: &Addr,
+    payload: &T,
+    signature: &Binary,
+    pubkey: &Binary,
+) -> Result<(), ContractError> {
+    // Cosmos addresses are derived from compressed secp256k1 public keys
+    if pubkey.len() != 33 {
+        return Err(ContractError::InvalidSignature {});
+    }
+    let mut sign_bytes = contract.as_bytes().to_vec();
+    sign_bytes.extend(to_vec(payload)?);
+    let hash = Sha256::digest(&sign_bytes);
+    if !api.secp256k1_verify(&hash, signature, pubkey)? {
+        return Err(ContractError::InvalidSignature {});
+    }
+
+    Ok(())
+}
+
+/// Derives the account address for a compressed secp256k1 public key
+fn pubkey_to_addr(api: &dyn Api, pubkey: &[u8]) -> StdResult<Addr> {
+    let hash = Ripemd160::digest(Sha256::digest(pubkey));
+    api.addr_humanize(&CanonicalAddr::from(hash.as_slice()))
 }
 
 pub fn query_config(deps: Deps) -> StdResult<State> {
@@ -206,7 +709,6 @@ pub fn query_config(deps: Deps) -> StdResult<State> {
     }
 }
 
-
 pub fn execute_set_ask(
     deps: DepsMut,
     env: Env,
@@ -222,17 +724,60 @@ pub fn execute_set_ask(
         reserve_for,
         finders_fee_bps,
         expires,
+        reserve_price,
+        min_bid_increment_bps,
     } = ask_info;
 
+    if sale_type == SaleType::DutchAuction {
+        return Err(ContractError::InvalidDutchAuction(
+            "use SetDutchAuction to list a Dutch auction".to_string(),
+        ));
+    }
     price_validate(deps.storage, &price)?;
-
-    Cw721Contract::<Empty, Empty>(collection.clone(), PhantomData, PhantomData).approval(
-        &deps.querier,
+    SUDO_PARAMS
+        .load(deps.storage)?
+        .ask_expiry
+        .is_valid(&env.block, expires)?;
+    if sale_type != SaleType::Auction
+        && (reserve_price.is_some() || min_bid_increment_bps.is_some())
+    {
+        return Err(ContractError::NotAuction {});
+    }
+    if let Some(reserve_price) = &reserve_price {
+        price_validate(deps.storage, reserve_price)?;
+        if reserve_price.denom != price.denom {
+            return Err(ContractError::InvalidDenom(reserve_price.denom.clone()));
+        }
+        if reserve_price.amount < price.amount {
+            return Err(ContractError::InvalidPrice {});
+        }
+    }
+    if let Some(min_bid_increment_bps) = min_bid_increment_bps {
+        if min_bid_increment_bps > MAX_FEE_BPS {
+            return Err(ContractError::InvalidBidIncrementBps(min_bid_increment_bps));
+        }
+    }
+    let min_bid_increment_percent =
+        min_bid_increment_bps.map(|bps| Decimal::from_ratio(bps, MAX_FEE_BPS));
+
+    if let Some(existing_ask) = asks().may_load(deps.storage, ask_key(&collection, token_id))? {
+        only_unlocked_auction(deps.storage, &existing_ask)?;
+    }
+
+    only_owner(deps.as_ref(), &env, &info, &collection, token_id)?;
+    Cw721Contract::<Empty, Empty>(collection.clone(), PhantomData, PhantomData).approval(
+        &deps.querier,
         token_id.to_string(),
         env.contract.address.to_string(),
         None,
     )?;
-    let listing_fee = may_pay(&info, NATIVE_DENOM)?;
+    let listing_fee_msgs = charge_listing_fee(
+        deps.storage,
+        &info,
+        &env.block,
+        ask_key(&collection, token_id),
+        &price.denom,
+    )?;
 
     let mut event = Event::new("set-ask")
         .add_attribute("collection", collection.to_string())
@@ -240,16 +785,7 @@ pub fn execute_set_ask(
         .add_attribute("sale_type", sale_type.to_string());
 
     if let Some(address) = reserve_for.clone() {
-        if address == info.sender {
-            return Err(ContractError::InvalidReserveAddress {
-                reason: "cannot reserve to the same address".to_string(),
-            });
-        }
-        if sale_type != SaleType::FixedPrice {
-            return Err(ContractError::InvalidReserveAddress {
-                reason: "can only reserve for fixed_price sales".to_string(),
-            });
-        }
+        reserve_for_validate(&info.sender, &address, &sale_type)?;
         event = event.add_attribute("reserve_for", address.to_string());
     };
 
@@ -261,18 +797,26 @@ pub fn execute_set_ask(
         token_id,
         seller: seller.clone(),
         price: price.amount,
+        denom: price.denom.clone(),
         funds_recipient,
         reserve_for,
         finders_fee_bps,
         expires_at: expires,
         is_active: true,
+        dutch_auction: None,
+        min_bid_increment_percent,
     };
 
     store_ask(deps.storage, &ask)?;
-    let mut res = Response::new();
-    // if listing_fee > Uint128::zero() {
-    //     fair_burn(listing_fee.u128(), None, &mut res);
-    // }
+    // the reserve price is stored apart from the ask so it stays hidden
+    let key = ask_key(&ask.collection, ask.token_id);
+    match reserve_price {
+        Some(reserve_price) => {
+            AUCTION_RESERVE_PRICES.save(deps.storage, key, &reserve_price.amount)?
+        }
+        None => AUCTION_RESERVE_PRICES.remove(deps.storage, key),
+    }
+    let res = Response::new().add_messages(listing_fee_msgs);
 
     let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Create)?;
     event = event
@@ -283,485 +827,5360 @@ pub fn execute_set_ask(
     Ok(res.add_submessages(hook).add_event(event))
 }
 
-fn price_validate(store: &dyn Storage, price: &Coin) -> Result<(), ContractError> {
-    if price.amount.is_zero() || price.denom != NATIVE_DENOM {
-        return Err(ContractError::InvalidPrice {});
-    }
-
-    // if price.amount < SUDO_PARAMS.load(store)?.min_price {
-    //     return Err(ContractError::PriceTooSmall(price.amount));
-    // }
-
-    Ok(())
-}
-fn store_ask(store: &mut dyn Storage, ask: &Ask) -> StdResult<()> {
-    asks().save(store, ask_key(&ask.collection, ask.token_id), ask)
-}
-
-fn prepare_ask_hook(deps: Deps, ask: &Ask, action: HookAction) -> StdResult<Vec<SubMsg>> {
-    let submsgs = ASK_HOOKS.prepare_hooks(deps.storage, |h| {
-        let msg = AskHookMsg { ask: ask.clone() };
-        let execute = WasmMsg::Execute {
-            contract_addr: h.to_string(),
-            msg: msg.into_binary(action.clone())?,
-            funds: vec![],
-        };
-        Ok(SubMsg::reply_on_error(execute, HookReply::Ask as u64))
-    })?;
-
-    Ok(submsgs)
-}
-enum HookReply {
-    Ask = 1,
-    Sale,
-    Bid,
-    CollectionBid,
-}
-
-impl From<u64> for HookReply {
-    fn from(item: u64) -> Self {
-        match item {
-            1 => HookReply::Ask,
-            2 => HookReply::Sale,
-            3 => HookReply::Bid,
-            4 => HookReply::CollectionBid,
-            _ => panic!("invalid reply type"),
-        }
-    }
-}
-
-pub fn execute_set_bid(
+/// Lists an NFT in a Dutch auction, the first buyer paying at least the current price wins
+pub fn execute_set_dutch_auction(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    sale_type: SaleType,
-    bid_info: BidInfo,
-    buy_now: bool,
+    auction_info: DutchAuctionInfo,
 ) -> Result<Response, ContractError> {
-    let BidInfo {
+    let DutchAuctionInfo {
         collection,
         token_id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        funds_recipient,
         finders_fee_bps,
         expires,
-        finder,
-    } = bid_info;
-    let params = SUDO_PARAMS.load(deps.storage)?;
+    } = auction_info;
 
-    if let Some(finder) = finder.clone() {
-        if info.sender == finder {
-            return Err(ContractError::InvalidFinder(
-                "bidder cannot be finder".to_string(),
-            ));
-        }
+    price_validate(deps.storage, &start_price)?;
+    price_validate(deps.storage, &end_price)?;
+    SUDO_PARAMS
+        .load(deps.storage)?
+        .ask_expiry
+        .is_valid(&env.block, expires)?;
+    if start_price.denom != end_price.denom {
+        return Err(ContractError::InvalidDenom(end_price.denom));
     }
-    let bid_price = must_pay(&info, NATIVE_DENOM)?;
-    if bid_price < params.min_price {
-        return Err(ContractError::PriceTooSmall(bid_price));
+    if start_price.amount <= end_price.amount {
+        return Err(ContractError::InvalidDutchAuction(
+            "start price must be higher than end price".to_string(),
+        ));
     }
-    params.bid_expiry.is_valid(&env.block, expires)?;
-    if let Some(finders_fee_bps) = finders_fee_bps {
-        if Decimal::percent(finders_fee_bps) > params.max_finders_fee_percent {
-            return Err(ContractError::InvalidFindersFeeBps(finders_fee_bps));
-        }
+    if end_time <= start_time || end_time <= env.block.time {
+        return Err(ContractError::InvalidDutchAuction(
+            "end time must be after start time and in the future".to_string(),
+        ));
     }
-    let bidder = info.sender;
-    let mut res = Response::new();
-    let bid_key = bid_key(&collection, token_id, &bidder);
-    let ask_key = ask_key(&collection, token_id);
-
-    if let Some(existing_bid) = bids().may_load(deps.storage, bid_key.clone())? {
-        bids().remove(deps.storage, bid_key)?;
-        let refund_bidder = BankMsg::Send {
-            to_address: bidder.to_string(),
-            amount: vec![coin(existing_bid.price.u128(), NATIVE_DENOM)],
-        };
-        res = res.add_message(refund_bidder)
+    if expires < end_time {
+        return Err(ContractError::InvalidDutchAuction(
+            "ask cannot expire before the auction ends".to_string(),
+        ));
     }
-    let existing_ask = asks().may_load(deps.storage, ask_key.clone())?;
 
-    if let Some(ask) = existing_ask.clone() {
-        if ask.is_expired(&env.block) {
-            return Err(ContractError::AskExpired {});
-        }
-        if !ask.is_active {
-            return Err(ContractError::AskNotActive {});
-        }
-        if let Some(reserved_for) = ask.reserve_for {
-            if reserved_for != bidder {
-                return Err(ContractError::TokenReserved {});
-            }
-        }
-    } else if buy_now {
-        return Err(ContractError::ItemNotForSale {});
+    if let Some(existing_ask) = asks().may_load(deps.storage, ask_key(&collection, token_id))? {
+        only_unlocked_auction(deps.storage, &existing_ask)?;
     }
-    let save_bid = |store| -> StdResult<_> {
-        let bid = Bid::new(
-            collection.clone(),
-            token_id,
-            bidder.clone(),
-            bid_price,
-            finders_fee_bps,
-            expires,
-        );
-        store_bid(store, &bid)?;
-        Ok(Some(bid))
-    };
 
-    let bid = match existing_ask {
-        Some(ask) => match ask.sale_type {
-            SaleType::FixedPrice => {
-                // check if bid matches ask price then execute the sale
-                // if the bid is lower than the ask price save the bid
-                // otherwise return an error
-                match bid_price.cmp(&ask.price) {
-                    Ordering::Greater => {
-                        return Err(ContractError::InvalidPrice {});
-                    }
-                    Ordering::Less => save_bid(deps.storage)?,
-                    Ordering::Equal => {
-                        asks().remove(deps.storage, ask_key)?;
-                        let owner = match Cw721Contract::<Empty, Empty>(
-                            ask.collection.clone(),
-                            PhantomData,
-                            PhantomData,
-                        )
-                        .owner_of(
-                            &deps.querier,
-                            ask.token_id.to_string(),
-                            false,
-                        ) {
-                            Ok(res) => res.owner,
-                            Err(_) => return Err(ContractError::InvalidListing {}),
-                        };
-                        if ask.seller != owner {
-                            return Err(ContractError::InvalidListing {});
-                        }
-                        finalize_sale(
-                            deps.as_ref(),
-                            ask,
-                            bid_price,
-                            bidder.clone(),
-                            finder,
-                            &mut res,
-                        )?;
-                        None
-                    }
-                }
-            }
-            SaleType::Auction => {
-                // check if bid price is equal or greater than ask price then place the bid
-                // otherwise return an error
-                match bid_price.cmp(&ask.price) {
-                    Ordering::Greater => save_bid(deps.storage)?,
-                    Ordering::Equal => save_bid(deps.storage)?,
-                    Ordering::Less => {
-                        return Err(ContractError::InvalidPrice {});
-                    }
-                }
-            }
-        },
-        None => save_bid(deps.storage)?,
-    };
-    let hook = if let Some(bid) = bid {
-        prepare_bid_hook(deps.as_ref(), &bid, HookAction::Create)?
-    } else {
-        vec![]
+    only_owner(deps.as_ref(), &env, &info, &collection, token_id)?;
+    Cw721Contract::<Empty, Empty>(collection.clone(), PhantomData, PhantomData).approval(
+        &deps.querier,
+        token_id.to_string(),
+        env.contract.address.to_string(),
+        None,
+    )?;
+    let listing_fee_msgs = charge_listing_fee(
+        deps.storage,
+        &info,
+        &env.block,
+        ask_key(&collection, token_id),
+        &start_price.denom,
+    )?;
+
+    let seller = info.sender;
+    let ask = Ask {
+        sale_type: SaleType::DutchAuction,
+        collection: collection.clone(),
+        token_id,
+        seller: seller.clone(),
+        price: start_price.amount,
+        denom: start_price.denom.clone(),
+        funds_recipient,
+        reserve_for: None,
+        finders_fee_bps,
+        expires_at: expires,
+        is_active: true,
+        dutch_auction: Some(DutchAuction {
+            start_price: start_price.amount,
+            end_price: end_price.amount,
+            start_time,
+            end_time,
+        }),
+        min_bid_increment_percent: None,
     };
+    store_ask(deps.storage, &ask)?;
+    AUCTION_RESERVE_PRICES.remove(deps.storage, ask_key(&collection, token_id));
 
-    let event = Event::new("set-bid")
+    let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Create)?;
+    let event = Event::new("set-dutch-auction")
         .add_attribute("collection", collection.to_string())
-        .add_attribute("sale_type", sale_type.to_string())
         .add_attribute("token_id", token_id.to_string())
-        .add_attribute("bidder", bidder)
-        .add_attribute("bid_price", bid_price.to_string())
+        .add_attribute("seller", seller)
+        .add_attribute("start_price", start_price.to_string())
+        .add_attribute("end_price", end_price.to_string())
+        .add_attribute("start_time", start_time.to_string())
+        .add_attribute("end_time", end_time.to_string())
         .add_attribute("expires", expires.to_string());
 
-    Ok(res.add_submessages(hook).add_event(event))
+    Ok(Response::new()
+        .add_messages(listing_fee_msgs)
+        .add_submessages(hook)
+        .add_event(event))
 }
 
-fn store_bid(store: &mut dyn Storage, bid: &Bid) -> StdResult<()> {
-    bids().save(
-        store,
-        bid_key(&bid.collection, bid.token_id, &bid.bidder),
-        bid,
-    )
+/// Removes the ask on a particular NFT, only the seller can remove it.
+/// The listing fee is refunded if the ask is removed within the grace period.
+pub fn execute_remove_ask(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: Addr,
+    token_id: TokenId,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let key = ask_key(&collection, token_id);
+    let ask = asks()
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::AskNotFound {})?;
+    if ask.seller != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    only_unlocked_auction(deps.storage, &ask)?;
+    remove_ask(deps.storage, key.clone())?;
+    let listing_fee_msgs = release_listing_fee(deps.storage, &env.block, key, Some(&ask.seller))?;
+
+    let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Delete)?;
+
+    let event = Event::new("remove-ask")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("seller", ask.seller);
+
+    Ok(Response::new()
+        .add_messages(listing_fee_msgs)
+        .add_submessages(hook)
+        .add_event(event))
 }
 
-fn finalize_sale(
-    deps: Deps,
-    ask: Ask,
-    price: Uint128,
-    buyer: Addr,
-    finder: Option<Addr>,
-    res: &mut Response,
-) -> StdResult<()> {
-    payout(
-        deps,
-        ask.collection.clone(),
-        price,
-        ask.funds_recipient
-            .clone()
-            .unwrap_or_else(|| ask.seller.clone()),
-        finder,
-        ask.finders_fee_bps,
-        res,
-    )?;
+/// Updates the ask price on a particular NFT, keeping the rest of the listing in place
+pub fn execute_update_ask_price(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: Addr,
+    token_id: TokenId,
+    price: Coin,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
 
-    let cw721_transfer_msg = Cw721ExecuteMsg::TransferNft {
-        token_id: ask.token_id.to_string(),
-        recipient: buyer.to_string(),
-    };
+    let key = ask_key(&collection, token_id);
+    let mut ask = asks()
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::AskNotFound {})?;
+    if ask.seller != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !ask.is_active {
+        return Err(ContractError::AskNotActive {});
+    }
+    if ask.is_expired(&env.block) {
+        return Err(ContractError::AskExpired {});
+    }
 
-    let exec_cw721_transfer = WasmMsg::Execute {
-        contract_addr: ask.collection.to_string(),
-        msg: to_binary(&cw721_transfer_msg)?,
-        funds: vec![],
-    };
-    res.messages.push(SubMsg::new(exec_cw721_transfer));
+    if ask.sale_type == SaleType::DutchAuction {
+        return Err(ContractError::InvalidDutchAuction(
+            "price of a Dutch auction follows its schedule".to_string(),
+        ));
+    }
+    only_unlocked_auction(deps.storage, &ask)?;
+    price_validate(deps.storage, &price)?;
+    if price.denom != ask.denom {
+        return Err(ContractError::InvalidDenom(price.denom));
+    }
+    if ask.price == price.amount {
+        return Err(ContractError::AskUnchanged {});
+    }
 
-    res.messages
-        .append(&mut prepare_sale_hook(deps, &ask, buyer.clone())?);
+    // `save` replaces the old entry, so the `collection_price` index follows the new price
+    ask.price = price.amount;
+    asks().save(deps.storage, key, &ask)?;
 
-    let event = Event::new("finalize-sale")
-        .add_attribute("collection", ask.collection.to_string())
-        .add_attribute("token_id", ask.token_id.to_string())
-        .add_attribute("seller", ask.seller.to_string())
-        .add_attribute("buyer", buyer.to_string())
+    let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Update)?;
+
+    let event = Event::new("update-ask-price")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("token_id", token_id.to_string())
         .add_attribute("price", price.to_string());
-    res.events.push(event);
 
-    Ok(())
+    Ok(Response::new().add_submessages(hook).add_event(event))
 }
-/// Check royalties are non-zero
-fn parse_royalties(royalty_info: Option<RoyaltyInfoResponse>) -> Option<RoyaltyInfoResponse> {
-    match royalty_info {
-        Some(royalty) => {
-            if royalty.share.is_zero() {
-                return None;
-            }
-            Some(royalty)
-        }
-        None => None,
-    }
-}
-fn payout(
-    deps: Deps,
+
+/// Checks the seller still owns the NFT and the marketplace still holds approval,
+/// and updates the active state of the ask to match.
+/// An approval will be removed when
+/// 1 - There is a transfer
+/// 2 - The approval expired (approvals can have different expiration times)
+pub fn execute_sync_ask(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
     collection: Addr,
-    payment: Uint128,
-    payment_recipient: Addr,
-    finder: Option<Addr>,
-    finders_fee_bps: Option<u64>,
-    res: &mut Response,
-) -> StdResult<()> {
-    let params = SUDO_PARAMS.load(deps.storage)?;
+    token_id: TokenId,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
 
-    // Append Fair Burn message
-    let network_fee = payment * params.trading_fee_percent / Uint128::from(100u128);
-    fair_burn(network_fee.u128(), None, res);
+    let key = ask_key(&collection, token_id);
+    let mut ask = asks()
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::AskNotFound {})?;
 
-    let collection_info: CollectionInfoResponse = deps
-        .querier
-        .query_wasm_smart(collection.clone(), &Sg721QueryMsg::CollectionInfo {})?;
-
-    let finders_fee = match finder {
-        Some(finder) => {
-            let finders_fee = finders_fee_bps
-                .map(|fee| (payment * Decimal::percent(fee) / Uint128::from(100u128)).u128())
-                .unwrap_or(0);
-            if finders_fee > 0 {
-                res.messages.push(SubMsg::new(BankMsg::Send {
-                    to_address: finder.to_string(),
-                    amount: vec![coin(finders_fee, NATIVE_DENOM)],
-                }));
-            }
-            finders_fee
-        }
-        None => 0,
-    };
+    let is_owner = seller_owns(deps.as_ref(), &env, &ask);
+    // natively minted tokens don't need an approval, the marketplace holds their registry
+    let is_approved = collection == env.contract.address
+        || Cw721Contract::<Empty, Empty>(collection.clone(), PhantomData, PhantomData)
+            .approval(
+                &deps.querier,
+                token_id.to_string(),
+                env.contract.address.to_string(),
+                None,
+            )
+            .is_ok();
 
-    match parse_royalties(collection_info.royalty_info) {
-        // If token supports royalties, payout shares to royalty recipient
-        Some(royalty) => {
-            let amount = coin((payment * royalty.share).u128(), NATIVE_DENOM);
-            if payment < (network_fee + Uint128::from(finders_fee) + amount.amount) {
-                return Err(StdError::generic_err("Fees exceed payment"));
-            }
-            res.messages.push(SubMsg::new(BankMsg::Send {
-                to_address: royalty.payment_address.to_string(),
-                amount: vec![amount.clone()],
-            }));
-            let event = Event::new("royalty-payout")
-                .add_attribute("collection", collection.to_string())
-                .add_attribute("amount", amount.to_string())
-                .add_attribute("recipient", royalty.payment_address.to_string());
-            res.events.push(event);
-            let seller_share_msg = BankMsg::Send {
-                to_address: payment_recipient.to_string(),
-                amount: vec![coin(
-                    (payment * (Decimal::one() - royalty.share) - network_fee).u128() - finders_fee,
-                    NATIVE_DENOM.to_string(),
-                )],
-            };
-            res.messages.push(SubMsg::new(seller_share_msg));
-        }
-        None => {
-            if payment < (network_fee + Uint128::from(finders_fee)) {
-                return Err(StdError::generic_err("Fees exceed payment"));
-            }
-            // If token doesn't support royalties, pay seller in full
-            let seller_share_msg = BankMsg::Send {
-                to_address: payment_recipient.to_string(),
-                amount: vec![coin(
-                    (payment - network_fee).u128() - finders_fee,
-                    NATIVE_DENOM.to_string(),
-                )],
-            };
-            res.messages.push(SubMsg::new(seller_share_msg));
-        }
+    let is_active = is_owner && is_approved;
+    if ask.is_active == is_active {
+        return Err(ContractError::AskUnchanged {});
     }
+    ask.is_active = is_active;
+    asks().save(deps.storage, key, &ask)?;
 
-    Ok(())
-}
+    let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Update)?;
 
-fn prepare_sale_hook(deps: Deps, ask: &Ask, buyer: Addr) -> StdResult<Vec<SubMsg>> {
-    let submsgs = SALE_HOOKS.prepare_hooks(deps.storage, |h| {
-        let msg = SaleHookMsg {
-            collection: ask.collection.to_string(),
-            token_id: ask.token_id,
-            price: coin(ask.price.clone().u128(), NATIVE_DENOM),
-            seller: ask.seller.to_string(),
-            buyer: buyer.to_string(),
-        };
-        let execute = WasmMsg::Execute {
-            contract_addr: h.to_string(),
-            msg: msg.into_binary()?,
-            funds: vec![],
-        };
-        Ok(SubMsg::reply_on_error(execute, HookReply::Sale as u64))
-    })?;
+    let event = Event::new("sync-ask")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("is_active", ask.is_active.to_string());
 
-    Ok(submsgs)
+    Ok(Response::new().add_submessages(hook).add_event(event))
 }
 
-fn prepare_bid_hook(deps: Deps, bid: &Bid, action: HookAction) -> StdResult<Vec<SubMsg>> {
-    let submsgs = BID_HOOKS.prepare_hooks(deps.storage, |h| {
-        let msg = BidHookMsg { bid: bid.clone() };
-        let execute = WasmMsg::Execute {
-            contract_addr: h.to_string(),
-            msg: msg.into_binary(action.clone())?,
-            funds: vec![],
-        };
-        Ok(SubMsg::reply_on_error(execute, HookReply::Bid as u64))
-    })?;
+/// Operators can set the active state of an ask after observing NFT transfers
+pub fn execute_update_ask_state(
+    deps: DepsMut,
+    info: MessageInfo,
+    collection: Addr,
+    token_id: TokenId,
+    active: bool,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    only_operator(deps.storage, &info)?;
 
-    Ok(submsgs)
+    let key = ask_key(&collection, token_id);
+    let mut ask = asks()
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::AskNotFound {})?;
+    if ask.is_active == active {
+        return Err(ContractError::AskUnchanged {});
+    }
+    ask.is_active = active;
+    asks().save(deps.storage, key, &ask)?;
+
+    let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Update)?;
+
+    let event = Event::new("update-ask-state")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("operator", info.sender)
+        .add_attribute("is_active", ask.is_active.to_string());
+
+    Ok(Response::new().add_submessages(hook).add_event(event))
 }
 
-/// Seller can accept a bid which transfers funds as well as the token. The bid may or may not be associated with an ask.
-pub fn execute_accept_bid(
+/// Operators can remove an ask once it has expired
+pub fn execute_remove_stale_ask(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     collection: Addr,
     token_id: TokenId,
-    bidder: Addr,
-    finder: Option<Addr>,
 ) -> Result<Response, ContractError> {
     nonpayable(&info)?;
-    only_owner(deps.as_ref(), &info, &collection, token_id)?;
-    only_tradable(deps.as_ref(), &env.block, &collection)?;
-    let bid_key = bid_key(&collection, token_id, &bidder);
-    let ask_key = ask_key(&collection, token_id);
+    only_operator(deps.storage, &info)?;
 
-    let bid = bids().load(deps.storage, bid_key.clone())?;
-    if bid.is_expired(&env.block) {
-        return Err(ContractError::BidExpired {});
+    let key = ask_key(&collection, token_id);
+    let ask = asks()
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::AskNotFound {})?;
+    if !ask.is_expired(&env.block) {
+        return Err(ContractError::AskNotExpired {});
+    }
+    // an ended auction with a bid is closed by `SettleAuction`, which pays the winner
+    only_unlocked_auction(deps.storage, &ask)?;
+    remove_ask(deps.storage, key.clone())?;
+    let listing_fee_msgs = release_listing_fee(deps.storage, &env.block, key, None)?;
+
+    let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Delete)?;
+
+    let event = Event::new("remove-stale-ask")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("operator", info.sender);
+
+    Ok(Response::new()
+        .add_messages(listing_fee_msgs)
+        .add_submessages(hook)
+        .add_event(event))
+}
+
+/// Operators can prune expired asks for a collection one page at a time.
+/// `limit` bounds the number of asks scanned, not the number removed.
+pub fn execute_remove_stale_asks(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: Addr,
+    start_after: Option<TokenId>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    only_operator(deps.storage, &info)?;
+
+    let limit = limit.unwrap_or(DEFAULT_BATCH_LIMIT).min(MAX_BATCH_LIMIT) as usize;
+    let start = start_after.map(|token_id| Bound::exclusive(ask_key(&collection, token_id)));
+
+    let scanned = asks()
+        .idx
+        .collection
+        .prefix(collection.clone())
+        .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut res = Response::new();
+    let mut removed = 0u32;
+    for ask in scanned.iter().filter(|ask| ask.is_expired(&env.block)) {
+        // ended auctions with a bid are left for `SettleAuction`
+        if auction_locked(deps.storage, ask)? {
+            continue;
+        }
+        let key = ask_key(&ask.collection, ask.token_id);
+        remove_ask(deps.storage, key.clone())?;
+        res = res
+            .add_messages(release_listing_fee(deps.storage, &env.block, key, None)?)
+            .add_submessages(prepare_ask_hook(deps.as_ref(), ask, HookAction::Delete)?);
+        removed += 1;
     }
 
-    if asks().may_load(deps.storage, ask_key.clone())?.is_some() {
-        asks().remove(deps.storage, ask_key)?;
+    let mut event = Event::new("remove-stale-asks")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("operator", info.sender)
+        .add_attribute("scanned", scanned.len().to_string())
+        .add_attribute("removed", removed.to_string());
+    if let Some(last) = scanned.last() {
+        event = event.add_attribute("last_token_id", last.token_id.to_string());
     }
 
-    // Create a temporary Ask
+    Ok(res.add_event(event))
+}
+
+/// Mints a native token to the minter and lists it in the same transaction.
+/// The ask price defaults to the `base_price` of the token in the accepted denom.
+/// The listing fee is charged as it is for `SetAsk`.
+pub fn execute_mint_and_list(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mint_msg: MintMsg,
+    list_info: ListInfo,
+) -> Result<Response, ContractError> {
+    let ListInfo {
+        sale_type,
+        price,
+        funds_recipient,
+        reserve_for,
+        finders_fee_bps,
+        expires,
+    } = list_info;
+
+    if sale_type == SaleType::DutchAuction {
+        return Err(ContractError::InvalidDutchAuction(
+            "use SetDutchAuction to list a Dutch auction".to_string(),
+        ));
+    }
+    let denom = DENOM.load(deps.storage)?;
+    let price = match price {
+        Some(price) => price,
+        None => mint_msg
+            .price
+            .iter()
+            .find(|coin| coin.denom == denom)
+            .cloned()
+            .ok_or(ContractError::InvalidPrice {})?,
+    };
+    price_validate(deps.storage, &price)?;
+    SUDO_PARAMS
+        .load(deps.storage)?
+        .ask_expiry
+        .is_valid(&env.block, expires)?;
+    if let Some(address) = &reserve_for {
+        reserve_for_validate(&info.sender, address, &sale_type)?;
+    }
+
+    // asks key tokens by a u32 id, so the next native token id has to fit
+    let next_token_id = query_config(deps.as_ref())?.num_tokens + 1;
+    let token_id = TokenId::try_from(next_token_id)
+        .map_err(|_| ContractError::TokenIdOutOfRange(next_token_id))?;
+
+    let listing_fee_msgs = charge_listing_fee(
+        deps.storage,
+        &info,
+        &env.block,
+        ask_key(&env.contract.address, token_id),
+        &price.denom,
+    )?;
+    // the funds only cover the listing fee, minting itself is free
+    let mint_info = MessageInfo {
+        sender: info.sender.clone(),
+        funds: vec![],
+    };
+    let res = handle_mint(deps.branch(), env.clone(), mint_info, mint_msg)?;
+
+    let seller = info.sender;
     let ask = Ask {
-        sale_type: SaleType::Auction,
-        collection: collection.clone(),
+        sale_type,
+        collection: env.contract.address,
         token_id,
-        price: bid.price,
-        expires_at: bid.expires_at,
+        seller: seller.clone(),
+        price: price.amount,
+        denom: price.denom.clone(),
+        funds_recipient,
+        reserve_for,
+        finders_fee_bps,
+        expires_at: expires,
         is_active: true,
-        seller: info.sender.clone(),
-        funds_recipient: Some(info.sender),
-        reserve_for: None,
-        finders_fee_bps: bid.finders_fee_bps,
+        dutch_auction: None,
+        min_bid_increment_percent: None,
     };
+    store_ask(deps.storage, &ask)?;
 
-    // Remove accepted bid
-    bids().remove(deps.storage, bid_key)?;
+    let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Create)?;
 
-    let mut res = Response::new();
+    let mut event = Event::new("set-ask")
+        .add_attribute("collection", ask.collection.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("sale_type", ask.sale_type.to_string());
+    if let Some(address) = &ask.reserve_for {
+        event = event.add_attribute("reserve_for", address.to_string());
+    }
+    event = event
+        .add_attribute("seller", seller)
+        .add_attribute("price", price.to_string())
+        .add_attribute("expires", expires.to_string());
 
-    // Transfer funds and NFT
+    Ok(res
+        .add_messages(listing_fee_msgs)
+        .add_submessages(hook)
+        .add_event(event))
+}
+
+/// Checks a token can be reserved for the given address
+fn reserve_for_validate(
+    seller: &Addr,
+    reserve_for: &Addr,
+    sale_type: &SaleType,
+) -> Result<(), ContractError> {
+    if reserve_for == seller {
+        return Err(ContractError::InvalidReserveAddress {
+            reason: "cannot reserve to the same address".to_string(),
+        });
+    }
+    if sale_type != &SaleType::FixedPrice {
+        return Err(ContractError::InvalidReserveAddress {
+            reason: "can only reserve for fixed_price sales".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Fills an ask that was signed off-chain by the seller instead of being stored in `asks()`
+pub fn execute_fill_signed_ask(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order: SignedAsk,
+    signature: Binary,
+    finder: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let api = deps.api;
+    let collection = api.addr_validate(&order.collection)?;
+    let seller = api.addr_validate(&order.seller)?;
+    let buyer = info.sender.clone();
+
+    if let Some(finder) = &finder {
+        if *finder == buyer {
+            return Err(ContractError::InvalidFinder(
+                "bidder cannot be finder".to_string(),
+            ));
+        }
+    }
+
+    verify_signature(
+        api,
+        &env.contract.address,
+        &order,
+        &signature,
+        &order.seller_pubkey,
+    )?;
+    if pubkey_to_addr(api, &order.seller_pubkey)? != seller {
+        return Err(ContractError::InvalidSignature {});
+    }
+
+    let nonce_key = (&seller, order.nonce);
+    if SIGNED_ORDER_NONCES.has(deps.storage, nonce_key) {
+        return Err(ContractError::OrderCancelledOrFilled {});
+    }
+    if order.expires_at <= env.block.time {
+        return Err(ContractError::AskExpired {});
+    }
+    if let Some(reserve_for) = &order.reserve_for {
+        if api.addr_validate(reserve_for)? != buyer {
+            return Err(ContractError::TokenReserved {});
+        }
+    }
+
+    price_validate(deps.storage, &order.price)?;
+    only_native_price(deps.storage, &order.price)?;
+    let payment = must_pay(&info, &order.price.denom)?;
+    if payment != order.price.amount {
+        return Err(ContractError::InvalidPrice {});
+    }
+    if let Some(finders_fee_bps) = order.finders_fee_bps {
+        let params = SUDO_PARAMS.load(deps.storage)?;
+        if Decimal::from_ratio(finders_fee_bps, MAX_FEE_BPS) > params.max_finders_fee_percent {
+            return Err(ContractError::InvalidFindersFeeBps(finders_fee_bps));
+        }
+    }
+
+    if collection != env.contract.address {
+        only_tradable(deps.as_ref(), &env.block, &collection)?;
+        Cw721Contract::<Empty, Empty>(collection.clone(), PhantomData, PhantomData).approval(
+            &deps.querier,
+            order.token_id.to_string(),
+            env.contract.address.to_string(),
+            None,
+        )?;
+    }
+    match token_owner(deps.as_ref(), &env, &collection, order.token_id) {
+        Ok(owner) if owner == seller => {}
+        _ => return Err(ContractError::InvalidListing {}),
+    }
+
+    SIGNED_ORDER_NONCES.save(deps.storage, nonce_key, &true)?;
+
+    // A stored ask for the same token is stale once the token is sold
+    let ask_key = ask_key(&collection, order.token_id);
+    let mut listing_fee_msgs = vec![];
+    if let Some(existing_ask) = asks().may_load(deps.storage, ask_key.clone())? {
+        only_closed_auction(deps.storage, &env.block, &existing_ask)?;
+        remove_ask(deps.storage, ask_key.clone())?;
+        listing_fee_msgs = release_listing_fee(deps.storage, &env.block, ask_key, None)?;
+    }
+
+    let ask = Ask {
+        sale_type: SaleType::FixedPrice,
+        collection: collection.clone(),
+        token_id: order.token_id,
+        seller: seller.clone(),
+        price: order.price.amount,
+        denom: order.price.denom.clone(),
+        funds_recipient: maybe_addr(api, order.funds_recipient.clone())?,
+        reserve_for: None,
+        finders_fee_bps: order.finders_fee_bps,
+        expires_at: order.expires_at,
+        is_active: true,
+        dutch_auction: None,
+        min_bid_increment_percent: None,
+    };
+
+    let mut res = Response::new().add_messages(listing_fee_msgs);
     finalize_sale(
-        deps.as_ref(),
+        deps.branch(),
+        &env,
         ask,
-        bid.price,
-        bidder.clone(),
+        order.price.amount,
+        buyer.clone(),
         finder,
         &mut res,
     )?;
 
-    let event = Event::new("accept-bid")
+    let event = Event::new("fill-signed-ask")
         .add_attribute("collection", collection.to_string())
-        .add_attribute("token_id", token_id.to_string())
-        .add_attribute("bidder", bidder)
-        .add_attribute("price", bid.price.to_string());
+        .add_attribute("token_id", order.token_id.to_string())
+        .add_attribute("seller", seller)
+        .add_attribute("buyer", buyer)
+        .add_attribute("nonce", order.nonce.to_string())
+        .add_attribute("price", order.price.to_string());
 
     Ok(res.add_event(event))
 }
 
-fn only_owner(
-    deps: Deps,
-    info: &MessageInfo,
-    collection: &Addr,
-    token_id: u32,
-) -> Result<OwnerOfResponse, ContractError> {
-    let res = Cw721Contract::<Empty, Empty>(collection.clone(), PhantomData, PhantomData)
-        .owner_of(&deps.querier, token_id.to_string(), false)?;
-    if res.owner != info.sender {
-        return Err(ContractError::UnauthorizedOwner {});
+/// Sellers can invalidate off-chain signed asks by nonce
+pub fn execute_cancel_signed_orders(
+    deps: DepsMut,
+    info: MessageInfo,
+    nonces: Vec<u64>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    for nonce in &nonces {
+        SIGNED_ORDER_NONCES.save(deps.storage, (&info.sender, *nonce), &true)?;
     }
 
-    Ok(res)
+    let nonces: Vec<String> = nonces.iter().map(|nonce| nonce.to_string()).collect();
+    let event = Event::new("cancel-signed-orders")
+        .add_attribute("seller", info.sender)
+        .add_attribute("nonces", nonces.join(","));
+
+    Ok(Response::new().add_event(event))
 }
 
-/// Checks that the collection is tradable
-fn only_tradable(deps: Deps, block: &BlockInfo, collection: &Addr) -> Result<bool, ContractError> {
-    let res: Result<CollectionInfoResponse, StdError> = deps
-        .querier
-        .query_wasm_smart(collection.clone(), &Sg721QueryMsg::CollectionInfo {});
+/// Collection creators can register royalties split between several recipients,
+/// which are paid instead of the sg721 royalty
+pub fn execute_set_royalties(
+    deps: DepsMut,
+    info: MessageInfo,
+    collection: Addr,
+    royalties: Vec<RoyaltyShareMsg>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    only_collection_creator(deps.as_ref(), &info, &collection)?;
 
-    match res {
-        Ok(collection_info) => match collection_info.start_trading_time {
-            Some(start_trading_time) => {
-                if start_trading_time > block.time {
-                    Err(ContractError::CollectionNotTradable {})
-                } else {
-                    Ok(true)
-                }
-            }
-            // not set by collection, so tradable
-            None => Ok(true),
-        },
-        // not supported by collection
-        Err(_) => Ok(true),
+    let mut event = Event::new("set-royalties").add_attribute("collection", collection.to_string());
+    if royalties.is_empty() {
+        ROYALTIES.remove(deps.storage, &collection);
+        return Ok(Response::new().add_event(event));
     }
-}
+
+    let params = SUDO_PARAMS.load(deps.storage)?;
+    let mut total_bps: u64 = 0;
+    let mut shares = vec![];
+    for royalty in royalties {
+        let recipient = deps.api.addr_validate(&royalty.recipient)?;
+        if shares
+            .iter()
+            .any(|share: &RoyaltyShare| share.recipient == recipient)
+        {
+            return Err(ContractError::DuplicateRecipient(recipient.to_string()));
+        }
+        if royalty.share_bps == 0 {
+            return Err(ContractError::InvalidRoyalties {});
+        }
+        total_bps = total_bps
+            .checked_add(royalty.share_bps)
+            .ok_or(ContractError::InvalidRoyalties {})?;
+        event = event
+            .add_attribute("recipient", recipient.to_string())
+            .add_attribute("share_bps", royalty.share_bps.to_string());
+        shares.push(RoyaltyShare {
+            recipient,
+            share: Decimal::from_ratio(royalty.share_bps, MAX_FEE_BPS),
+        });
+    }
+    if total_bps > MAX_FEE_BPS
+        || Decimal::from_ratio(total_bps, MAX_FEE_BPS) > params.max_royalty_percent
+    {
+        return Err(ContractError::InvalidRoyalties {});
+    }
+    ROYALTIES.save(deps.storage, &collection, &shares)?;
+
+    Ok(Response::new().add_event(event))
+}
+
+/// Checks the denom is an enabled payment asset and returns its type
+fn asset_validate(store: &dyn Storage, denom: &str) -> Result<AssetType, ContractError> {
+    match PAYMENT_ASSETS.may_load(store, denom)? {
+        Some(asset) if asset.enabled => Ok(asset.asset_type),
+        _ => Err(ContractError::InvalidDenom(denom.to_string())),
+    }
+}
+
+/// Signed orders are paid with the funds sent along with the message, which can't carry
+/// CW20 tokens
+fn only_native_price(store: &dyn Storage, price: &Coin) -> Result<(), ContractError> {
+    if asset_validate(store, &price.denom)? == AssetType::Cw20 {
+        return Err(ContractError::NativeDenomRequired(price.denom.clone()));
+    }
+
+    Ok(())
+}
+
+/// Takes the single native coin sent along with the message as payment
+fn native_payment(store: &dyn Storage, info: &MessageInfo) -> Result<Coin, ContractError> {
+    let payment = one_coin(info)?;
+    if asset_validate(store, &payment.denom)? != AssetType::Native {
+        return Err(ContractError::InvalidDenom(payment.denom));
+    }
+
+    Ok(payment)
+}
+
+fn asset_type(store: &dyn Storage, denom: &str) -> StdResult<AssetType> {
+    Ok(PAYMENT_ASSETS
+        .may_load(store, denom)?
+        .map_or(AssetType::Native, |asset| asset.asset_type))
+}
+
+/// Sends funds as a bank transfer or a CW20 transfer, depending on the asset
+fn transfer_msg(store: &dyn Storage, recipient: &Addr, amount: Coin) -> StdResult<CosmosMsg> {
+    let msg = match asset_type(store, &amount.denom)? {
+        AssetType::Native => BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![amount],
+        }
+        .into(),
+        AssetType::Cw20 => WasmMsg::Execute {
+            contract_addr: amount.denom,
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: amount.amount,
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    };
+
+    Ok(msg)
+}
+
+/// Burns funds held by the contract, depending on the asset
+fn burn_msg(store: &dyn Storage, amount: Coin) -> StdResult<CosmosMsg> {
+    let msg = match asset_type(store, &amount.denom)? {
+        AssetType::Native => BankMsg::Burn {
+            amount: vec![amount],
+        }
+        .into(),
+        AssetType::Cw20 => WasmMsg::Execute {
+            contract_addr: amount.denom,
+            msg: to_binary(&Cw20ExecuteMsg::Burn {
+                amount: amount.amount,
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    };
+
+    Ok(msg)
+}
+
+/// Splits a trading fee between the recipients in `FEE_DISTRIBUTION`.
+/// Rounding dust is burned along with the burn share, or goes to the first
+/// recipient when nothing is burned.
+fn distribute_fee(store: &dyn Storage, fee: Coin, res: &mut Response) -> StdResult<()> {
+    let fee_distribution = FEE_DISTRIBUTION.load(store)?;
+    let mut event = Event::new("fee-distribution").add_attribute("fee", fee.to_string());
+
+    let mut amounts: Vec<(Addr, Uint128)> = fee_distribution
+        .shares
+        .into_iter()
+        .map(|share| {
+            let amount = fee
+                .amount
+                .multiply_ratio(share.share_bps, MAX_FEE_BPS as u128);
+            (share.recipient, amount)
+        })
+        .collect();
+    let distributed = amounts
+        .iter()
+        .fold(Uint128::zero(), |total, (_, amount)| total + amount);
+    let mut burn_amount = fee.amount - distributed;
+    if fee_distribution.burn_bps == 0 {
+        if let Some((_, amount)) = amounts.first_mut() {
+            *amount += burn_amount;
+            burn_amount = Uint128::zero();
+        }
+    }
+
+    for (recipient, amount) in amounts {
+        if amount.is_zero() {
+            continue;
+        }
+        event = event.add_attribute(recipient.to_string(), amount.to_string());
+        res.messages.push(SubMsg::new(transfer_msg(
+            store,
+            &recipient,
+            coin(amount.u128(), &fee.denom),
+        )?));
+    }
+    if !burn_amount.is_zero() {
+        res.messages.push(SubMsg::new(burn_msg(
+            store,
+            coin(burn_amount.u128(), &fee.denom),
+        )?));
+    }
+    res.events
+        .push(event.add_attribute("burn", burn_amount.to_string()));
+
+    Ok(())
+}
+
+/// Sends a fee to the given destination
+fn fee_msg(store: &dyn Storage, destination: &FeeDestination, fee: Coin) -> StdResult<CosmosMsg> {
+    match destination {
+        FeeDestination::Burn => burn_msg(store, fee),
+        FeeDestination::CommunityPool => match asset_type(store, &fee.denom)? {
+            AssetType::Native => Ok(create_fund_community_pool_msg(vec![fee])),
+            // the community pool can only be funded with bank coins
+            AssetType::Cw20 => Err(StdError::generic_err(format!(
+                "cannot fund the community pool with cw20 token {}",
+                fee.denom
+            ))),
+        },
+        FeeDestination::FeeCollector { address } => transfer_msg(store, address, fee),
+    }
+}
+
+/// Checks the sender paid exactly the listing fee for an ask priced in `denom`.
+/// Asks priced in CW20 tokens pay the global listing fee in the default denom.
+/// The fee is held during the grace period, otherwise it is routed right away.
+fn charge_listing_fee(
+    store: &mut dyn Storage,
+    info: &MessageInfo,
+    block: &BlockInfo,
+    key: AskKey,
+    denom: &str,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let params = SUDO_PARAMS.load(store)?;
+    let (fee_denom, fee) = match asset_type(store, denom)? {
+        AssetType::Native => (
+            denom.to_string(),
+            AssetParams::load(store, denom)?.listing_fee,
+        ),
+        AssetType::Cw20 => (DENOM.load(store)?, params.listing_fee),
+    };
+    let paid = match info.funds.as_slice() {
+        [] => Uint128::zero(),
+        [payment] if payment.denom == fee_denom => payment.amount,
+        _ => Uint128::zero(),
+    };
+    if paid != fee || (fee.is_zero() && !info.funds.is_empty()) {
+        return Err(ContractError::InvalidListingFee(paid));
+    }
+
+    // a seller relisting forfeits the fee of their previous listing, while a new owner
+    // listing the token refunds the fee the previous seller paid
+    let mut msgs = match asks().may_load(store, key.clone())? {
+        Some(ask) if ask.seller != info.sender => {
+            refund_listing_fee(store, key.clone(), &ask.seller)?
+        }
+        _ => release_listing_fee(store, block, key.clone(), None)?,
+    };
+    if fee.is_zero() {
+        return Ok(msgs);
+    }
+    let fee = coin(fee.u128(), fee_denom);
+    if params.listing_fee_grace_period == 0 {
+        msgs.push(fee_msg(store, &params.listing_fee_destination, fee)?);
+    } else {
+        LISTING_FEES.save(
+            store,
+            key,
+            &ListingFee {
+                fee,
+                paid_at: block.time,
+            },
+        )?;
+    }
+
+    Ok(msgs)
+}
+
+/// Releases the listing fee held for an ask that is being closed.
+/// It is refunded to `seller` if the ask is cancelled within the grace period,
+/// otherwise it is routed to the listing fee destination.
+fn release_listing_fee(
+    store: &mut dyn Storage,
+    block: &BlockInfo,
+    key: AskKey,
+    seller: Option<&Addr>,
+) -> StdResult<Vec<CosmosMsg>> {
+    let listing_fee = match LISTING_FEES.may_load(store, key.clone())? {
+        Some(listing_fee) => listing_fee,
+        None => return Ok(vec![]),
+    };
+    LISTING_FEES.remove(store, key);
+
+    let params = SUDO_PARAMS.load(store)?;
+    if let Some(seller) = seller {
+        let grace_period_end = listing_fee
+            .paid_at
+            .plus_seconds(params.listing_fee_grace_period);
+        if block.time < grace_period_end {
+            return Ok(vec![transfer_msg(store, seller, listing_fee.fee)?]);
+        }
+    }
+
+    Ok(vec![fee_msg(
+        store,
+        &params.listing_fee_destination,
+        listing_fee.fee,
+    )?])
+}
+
+/// Refunds the listing fee held for an ask in full, regardless of the grace period
+fn refund_listing_fee(
+    store: &mut dyn Storage,
+    key: AskKey,
+    seller: &Addr,
+) -> StdResult<Vec<CosmosMsg>> {
+    let listing_fee = match LISTING_FEES.may_load(store, key.clone())? {
+        Some(listing_fee) => listing_fee,
+        None => return Ok(vec![]),
+    };
+    LISTING_FEES.remove(store, key);
+
+    Ok(vec![transfer_msg(store, seller, listing_fee.fee)?])
+}
+
+fn price_validate(store: &dyn Storage, price: &Coin) -> Result<(), ContractError> {
+    if price.amount.is_zero() {
+        return Err(ContractError::InvalidPrice {});
+    }
+    asset_validate(store, &price.denom)?;
+
+    if price.amount < AssetParams::load(store, &price.denom)?.min_price {
+        return Err(ContractError::PriceTooSmall(price.amount));
+    }
+
+    Ok(())
+}
+fn store_ask(store: &mut dyn Storage, ask: &Ask) -> StdResult<()> {
+    asks().save(store, ask_key(&ask.collection, ask.token_id), ask)
+}
+
+/// Removes an ask together with its auction reserve price
+fn remove_ask(store: &mut dyn Storage, key: AskKey) -> StdResult<()> {
+    AUCTION_RESERVE_PRICES.remove(store, key.clone());
+    asks().remove(store, key)
+}
+
+fn prepare_ask_hook(deps: Deps, ask: &Ask, action: HookAction) -> StdResult<Vec<SubMsg>> {
+    let submsgs = ASK_HOOKS.prepare_hooks(deps.storage, |h| {
+        let msg = AskHookMsg { ask: ask.clone() };
+        let execute = WasmMsg::Execute {
+            contract_addr: h.to_string(),
+            msg: msg.into_binary(action.clone())?,
+            funds: vec![],
+        };
+        Ok(SubMsg::reply_on_error(execute, HookReply::Ask as u64))
+    })?;
+
+    Ok(submsgs)
+}
+enum HookReply {
+    Ask = 1,
+    Sale,
+    Bid,
+    CollectionBid,
+}
+
+impl From<u64> for HookReply {
+    fn from(item: u64) -> Self {
+        match item {
+            1 => HookReply::Ask,
+            2 => HookReply::Sale,
+            3 => HookReply::Bid,
+            4 => HookReply::CollectionBid,
+            _ => panic!("invalid reply type"),
+        }
+    }
+}
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(_deps: Deps, _env: Env, _msg: QueryMsg) -> StdResult<Binary> {
-    unimplemented!()
+pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let action = match msg.id.into() {
+        HookReply::Ask => "ask-hook-failed",
+        HookReply::Sale => "sale-hook-failed",
+        HookReply::Bid => "bid-hook-failed",
+        HookReply::CollectionBid => "collection-bid-hook-failed",
+    };
+    // hooks are dispatched with `reply_on_error`, so the result is always an error
+    let error = msg.result.into_result().err().unwrap_or_default();
+
+    Ok(Response::new()
+        .add_attribute("action", action)
+        .add_attribute("error", error))
 }
 
-#[cfg(test)]
-mod tests {}
+pub fn execute_set_bid(
+    mut deps: DepsMut,
+    env: Env,
+    bidder: Addr,
+    payment: Coin,
+    sale_type: SaleType,
+    bid_info: BidInfo,
+    buy_now: bool,
+) -> Result<Response, ContractError> {
+    let BidInfo {
+        collection,
+        token_id,
+        finders_fee_bps,
+        expires,
+        finder,
+    } = bid_info;
+    let params = SUDO_PARAMS.load(deps.storage)?;
+
+    if let Some(finder) = finder.clone() {
+        if bidder == finder {
+            return Err(ContractError::InvalidFinder(
+                "bidder cannot be finder".to_string(),
+            ));
+        }
+    }
+    let Coin {
+        denom,
+        amount: bid_price,
+    } = payment;
+    if bid_price < AssetParams::load(deps.storage, &denom)?.min_price {
+        return Err(ContractError::PriceTooSmall(bid_price));
+    }
+    params.bid_expiry.is_valid(&env.block, expires)?;
+    if let Some(finders_fee_bps) = finders_fee_bps {
+        if Decimal::from_ratio(finders_fee_bps, MAX_FEE_BPS) > params.max_finders_fee_percent {
+            return Err(ContractError::InvalidFindersFeeBps(finders_fee_bps));
+        }
+    }
+    let mut res = Response::new();
+    let bid_key = bid_key(&collection, token_id, &bidder);
+    let ask_key = ask_key(&collection, token_id);
+
+    let existing_bid = bids().may_load(deps.storage, bid_key.clone())?;
+    if let Some(existing_bid) = &existing_bid {
+        bids().remove(deps.storage, bid_key)?;
+        let refund_bidder = transfer_msg(
+            deps.storage,
+            &bidder,
+            coin(existing_bid.price.u128(), &existing_bid.denom),
+        )?;
+        res = res.add_message(refund_bidder)
+    }
+    let existing_ask = asks().may_load(deps.storage, ask_key.clone())?;
+
+    if let Some(ask) = existing_ask.clone() {
+        if ask.is_expired(&env.block) {
+            return Err(ContractError::AskExpired {});
+        }
+        if !ask.is_active {
+            return Err(ContractError::AskNotActive {});
+        }
+        if ask.denom != denom {
+            return Err(ContractError::InvalidDenom(denom));
+        }
+        if let Some(reserved_for) = ask.reserve_for {
+            if reserved_for != bidder {
+                return Err(ContractError::TokenReserved {});
+            }
+        }
+    } else if buy_now {
+        return Err(ContractError::ItemNotForSale {});
+    }
+    let save_bid = |store| -> StdResult<_> {
+        let bid = Bid::new(
+            collection.clone(),
+            token_id,
+            bidder.clone(),
+            coin(bid_price.u128(), &denom),
+            finders_fee_bps,
+            expires,
+        );
+        store_bid(store, &bid)?;
+        Ok(Some(bid))
+    };
+
+    let bid = match existing_ask {
+        Some(ask) => match ask.sale_type {
+            SaleType::FixedPrice => {
+                // check if bid matches ask price then execute the sale
+                // if the bid is lower than the ask price save the bid
+                // otherwise return an error
+                match bid_price.cmp(&ask.price) {
+                    Ordering::Greater => {
+                        return Err(ContractError::InvalidPrice {});
+                    }
+                    Ordering::Less => save_bid(deps.storage)?,
+                    Ordering::Equal => {
+                        remove_ask(deps.storage, ask_key.clone())?;
+                        res = res.add_messages(release_listing_fee(
+                            deps.storage,
+                            &env.block,
+                            ask_key,
+                            None,
+                        )?);
+                        if !seller_owns(deps.as_ref(), &env, &ask) {
+                            return Err(ContractError::InvalidListing {});
+                        }
+                        finalize_sale(
+                            deps.branch(),
+                            &env,
+                            ask,
+                            bid_price,
+                            bidder.clone(),
+                            finder,
+                            &mut res,
+                        )?;
+                        None
+                    }
+                }
+            }
+            SaleType::DutchAuction => {
+                // a bid covering the current price buys the NFT at that price,
+                // lower bids are kept as offers the seller can accept
+                let current_price = ask.current_price(env.block.time);
+                if bid_price < current_price {
+                    if buy_now {
+                        return Err(ContractError::InvalidPrice {});
+                    }
+                    save_bid(deps.storage)?
+                } else {
+                    remove_ask(deps.storage, ask_key.clone())?;
+                    res = res.add_messages(release_listing_fee(
+                        deps.storage,
+                        &env.block,
+                        ask_key,
+                        None,
+                    )?);
+                    if !seller_owns(deps.as_ref(), &env, &ask) {
+                        return Err(ContractError::InvalidListing {});
+                    }
+                    let overpayment = bid_price - current_price;
+                    if !overpayment.is_zero() {
+                        res = res.add_message(transfer_msg(
+                            deps.storage,
+                            &bidder,
+                            coin(overpayment.u128(), &denom),
+                        )?);
+                    }
+                    finalize_sale(
+                        deps.branch(),
+                        &env,
+                        ask,
+                        current_price,
+                        bidder.clone(),
+                        finder,
+                        &mut res,
+                    )?;
+                    None
+                }
+            }
+            SaleType::Auction => {
+                // check if bid price is equal or greater than ask price
+                // otherwise return an error
+                if bid_price < ask.price {
+                    return Err(ContractError::InvalidPrice {});
+                }
+                if let Some(reserve_price) =
+                    AUCTION_RESERVE_PRICES.may_load(deps.storage, ask_key.clone())?
+                {
+                    if bid_price < reserve_price {
+                        return Err(ContractError::ReservePriceNotMet {});
+                    }
+                }
+                // the new bid must beat the current highest bid, which is refunded.
+                // The bidder's own bid was refunded above but still counts, so the
+                // top bidder can't lower their bid by bidding again.
+                let highest_bid = highest_bid(deps.storage, &collection, token_id)?
+                    .into_iter()
+                    .chain(existing_bid)
+                    .max_by_key(|bid| bid.price);
+                if let Some(highest_bid) = highest_bid {
+                    if bid_price <= highest_bid.price {
+                        return Err(ContractError::BidTooLow {});
+                    }
+                    let increment_percent = ask
+                        .min_bid_increment_percent
+                        .unwrap_or(params.min_bid_increment_percent);
+                    let min_bid = highest_bid.price + highest_bid.price * increment_percent;
+                    if bid_price < min_bid {
+                        return Err(ContractError::BidIncrementTooSmall(min_bid));
+                    }
+                    if highest_bid.bidder != bidder {
+                        bids().remove(
+                            deps.storage,
+                            (collection.clone(), token_id, highest_bid.bidder.clone()),
+                        )?;
+                        res = res
+                            .add_message(transfer_msg(
+                                deps.storage,
+                                &highest_bid.bidder,
+                                coin(highest_bid.price.u128(), &highest_bid.denom),
+                            )?)
+                            .add_submessages(prepare_bid_hook(
+                                deps.as_ref(),
+                                &highest_bid,
+                                HookAction::Delete,
+                            )?);
+                    }
+                }
+                // a bid landing close to the end of the auction extends it
+                let mut auction_end = ask.expires_at;
+                let window_start = ask
+                    .expires_at
+                    .seconds()
+                    .saturating_sub(params.auction_extension_window);
+                if params.auction_extension_window > 0 && env.block.time.seconds() >= window_start {
+                    let new_expires_at = env
+                        .block
+                        .time
+                        .plus_seconds(params.auction_extension_duration);
+                    if new_expires_at > ask.expires_at {
+                        auction_end = new_expires_at;
+                        let mut ask = ask.clone();
+                        ask.expires_at = new_expires_at;
+                        store_ask(deps.storage, &ask)?;
+                        let event = Event::new("auction-extended")
+                            .add_attribute("collection", collection.to_string())
+                            .add_attribute("token_id", token_id.to_string())
+                            .add_attribute("expires_at", new_expires_at.to_string());
+                        res = res
+                            .add_submessages(prepare_ask_hook(
+                                deps.as_ref(),
+                                &ask,
+                                HookAction::Update,
+                            )?)
+                            .add_event(event);
+                    }
+                }
+                // the winning bid has to stay valid until the auction can be settled
+                if expires < auction_end {
+                    return Err(ContractError::BidExpiresBeforeAuctionEnd {});
+                }
+                save_bid(deps.storage)?
+            }
+        },
+        None => save_bid(deps.storage)?,
+    };
+    let hook = if let Some(bid) = bid {
+        prepare_bid_hook(deps.as_ref(), &bid, HookAction::Create)?
+    } else {
+        vec![]
+    };
+
+    let event = Event::new("set-bid")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("sale_type", sale_type.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("bidder", bidder)
+        .add_attribute("bid_price", bid_price.to_string())
+        .add_attribute("expires", expires.to_string());
+
+    Ok(res.add_submessages(hook).add_event(event))
+}
+
+/// Removes a bid made by the bidder. Bidders can only remove their own bids
+pub fn execute_remove_bid(
+    deps: DepsMut,
+    info: MessageInfo,
+    collection: Addr,
+    token_id: TokenId,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let bidder = info.sender;
+
+    let key = bid_key(&collection, token_id, &bidder);
+    let bid = bids()
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::BidNotFound {})?;
+
+    // the highest bid on an auction stays locked until the auction is settled
+    if let Some(ask) = asks().may_load(deps.storage, ask_key(&collection, token_id))? {
+        if ask.sale_type == SaleType::Auction
+            && highest_bid(deps.storage, &collection, token_id)?.as_ref() == Some(&bid)
+        {
+            return Err(ContractError::AuctionBidLocked {});
+        }
+    }
+    bids().remove(deps.storage, key)?;
+
+    let refund_bidder_msg = transfer_msg(
+        deps.storage,
+        &bid.bidder,
+        coin(bid.price.u128(), &bid.denom),
+    )?;
+
+    let hook = prepare_bid_hook(deps.as_ref(), &bid, HookAction::Delete)?;
+
+    let event = Event::new("remove-bid")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("bidder", bidder);
+
+    Ok(Response::new()
+        .add_message(refund_bidder_msg)
+        .add_submessages(hook)
+        .add_event(event))
+}
+
+/// Operators can remove a bid once it has been expired for longer than `stale_bid_duration`.
+/// The bidder is refunded minus the removal reward, which goes to the operator.
+pub fn execute_remove_stale_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: Addr,
+    token_id: TokenId,
+    bidder: Addr,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let operator = only_operator(deps.storage, &info)?;
+
+    let key = bid_key(&collection, token_id, &bidder);
+    let bid = bids()
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::BidNotFound {})?;
+
+    let params = SUDO_PARAMS.load(deps.storage)?;
+    let stale_time = (Expiration::AtTime(bid.expires_at) + params.stale_bid_duration)?;
+    if !stale_time.is_expired(&env.block) {
+        return Err(ContractError::BidNotStale {});
+    }
+
+    // bid is stale, refund bidder and reward operator
+    bids().remove(deps.storage, key)?;
+
+    let reward = bid.price * params.bid_removal_reward_percent;
+    let refund = bid.price.checked_sub(reward).map_err(StdError::overflow)?;
+
+    let mut res = Response::new();
+    if !refund.is_zero() {
+        res = res.add_message(transfer_msg(
+            deps.storage,
+            &bid.bidder,
+            coin(refund.u128(), &bid.denom),
+        )?);
+    }
+    if !reward.is_zero() {
+        res = res.add_message(transfer_msg(
+            deps.storage,
+            &operator,
+            coin(reward.u128(), &bid.denom),
+        )?);
+    }
+
+    let hook = prepare_bid_hook(deps.as_ref(), &bid, HookAction::Delete)?;
+
+    let event = Event::new("remove-stale-bid")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("bidder", bidder)
+        .add_attribute("operator", operator)
+        .add_attribute("reward", reward.to_string());
+
+    Ok(res.add_submessages(hook).add_event(event))
+}
+
+/// Returns the highest bid placed on a token
+fn highest_bid(
+    store: &dyn Storage,
+    collection: &Addr,
+    token_id: TokenId,
+) -> StdResult<Option<Bid>> {
+    let bids = bids()
+        .idx
+        .collection_token_id
+        .prefix((collection.clone(), token_id))
+        .range(store, None, None, cosmwasm_std::Order::Ascending)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(bids.into_iter().max_by_key(|bid| bid.price))
+}
+
+/// An auction with a bid can't be relisted, repriced or removed until it is settled
+fn only_unlocked_auction(store: &dyn Storage, ask: &Ask) -> Result<(), ContractError> {
+    if auction_locked(store, ask)? {
+        return Err(ContractError::AuctionLocked {});
+    }
+    Ok(())
+}
+
+/// Checks whether an auction has a bid that can win it, which only `SettleAuction` can close
+fn auction_locked(store: &dyn Storage, ask: &Ask) -> StdResult<bool> {
+    if ask.sale_type != SaleType::Auction {
+        return Ok(false);
+    }
+    Ok(highest_bid(store, &ask.collection, ask.token_id)?
+        .is_some_and(|bid| bid_outlasts(&bid, ask)))
+}
+
+/// A token listed in an auction can only be sold some other way once the auction
+/// has ended without a bid to settle
+fn only_closed_auction(
+    store: &dyn Storage,
+    block: &BlockInfo,
+    ask: &Ask,
+) -> Result<(), ContractError> {
+    if ask.sale_type == SaleType::Auction && !ask.is_expired(block) {
+        return Err(ContractError::AuctionNotEnded {});
+    }
+    only_unlocked_auction(store, ask)
+}
+
+/// Auction bids are checked to outlast the auction when they are placed, so one that
+/// doesn't can't win it, no matter how late the auction is settled
+fn bid_outlasts(bid: &Bid, ask: &Ask) -> bool {
+    bid.expires_at >= ask.expires_at
+}
+
+/// Settles an auction once it has ended, callable by anyone.
+/// The highest bid wins if it outlasts the auction, otherwise the listing is closed and
+/// the NFT stays with the seller. Bids below the ask or reserve price were already
+/// rejected when they were placed.
+pub fn execute_settle_auction(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: Addr,
+    token_id: TokenId,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let ask_key = ask_key(&collection, token_id);
+    let ask = asks()
+        .may_load(deps.storage, ask_key.clone())?
+        .ok_or(ContractError::AskNotFound {})?;
+    if ask.sale_type != SaleType::Auction {
+        return Err(ContractError::NotAuction {});
+    }
+    if !ask.is_expired(&env.block) {
+        return Err(ContractError::AuctionNotEnded {});
+    }
+    remove_ask(deps.storage, ask_key.clone())?;
+
+    let mut res = Response::new()
+        .add_messages(release_listing_fee(
+            deps.storage,
+            &env.block,
+            ask_key,
+            None,
+        )?)
+        .add_submessages(prepare_ask_hook(deps.as_ref(), &ask, HookAction::Delete)?);
+    let mut event = Event::new("settle-auction")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("seller", ask.seller.to_string());
+
+    let winning_bid =
+        highest_bid(deps.storage, &collection, token_id)?.filter(|bid| bid_outlasts(bid, &ask));
+    let bid = match winning_bid {
+        Some(bid) => bid,
+        None => {
+            event = event.add_attribute("result", "no_sale");
+            return Ok(res.add_event(event));
+        }
+    };
+    bids().remove(deps.storage, bid_key(&collection, token_id, &bid.bidder))?;
+    res = res.add_submessages(prepare_bid_hook(deps.as_ref(), &bid, HookAction::Delete)?);
+
+    // if the seller no longer holds the NFT, the auction is void and the winner is refunded
+    if !seller_owns(deps.as_ref(), &env, &ask) {
+        event = event
+            .add_attribute("result", "invalid_listing")
+            .add_attribute("bidder", bid.bidder.to_string());
+        let refund_bidder_msg = transfer_msg(
+            deps.storage,
+            &bid.bidder,
+            coin(bid.price.u128(), &bid.denom),
+        )?;
+        return Ok(res.add_message(refund_bidder_msg).add_event(event));
+    }
+
+    finalize_sale(
+        deps.branch(),
+        &env,
+        ask,
+        bid.price,
+        bid.bidder.clone(),
+        None,
+        &mut res,
+    )?;
+
+    event = event
+        .add_attribute("result", "sold")
+        .add_attribute("bidder", bid.bidder)
+        .add_attribute("price", bid.price.to_string());
+
+    Ok(res.add_event(event))
+}
+
+fn store_bid(store: &mut dyn Storage, bid: &Bid) -> StdResult<()> {
+    bids().save(
+        store,
+        bid_key(&bid.collection, bid.token_id, &bid.bidder),
+        bid,
+    )
+}
+
+fn finalize_sale(
+    deps: DepsMut,
+    env: &Env,
+    ask: Ask,
+    price: Uint128,
+    buyer: Addr,
+    finder: Option<Addr>,
+    res: &mut Response,
+) -> StdResult<()> {
+    payout(
+        deps.as_ref(),
+        ask.collection.clone(),
+        collection_royalties(
+            deps.as_ref(),
+            &ask.collection,
+            ask.token_id,
+            &coin(price.u128(), &ask.denom),
+        )?,
+        coin(price.u128(), &ask.denom),
+        ask.funds_recipient
+            .clone()
+            .unwrap_or_else(|| ask.seller.clone()),
+        finder,
+        ask.finders_fee_bps,
+        res,
+    )?;
+
+    if ask.collection == env.contract.address {
+        // natively minted tokens change owner in `tokens()`
+        tokens().update(deps.storage, ask.token_id.into(), |token| match token {
+            Some(mut token) => {
+                token.owner = buyer.clone();
+                Ok(token)
+            }
+            None => Err(StdError::not_found("TokenInfo")),
+        })?;
+    } else {
+        let cw721_transfer_msg = Cw721ExecuteMsg::TransferNft {
+            token_id: ask.token_id.to_string(),
+            recipient: buyer.to_string(),
+        };
+
+        let exec_cw721_transfer = WasmMsg::Execute {
+            contract_addr: ask.collection.to_string(),
+            msg: to_binary(&cw721_transfer_msg)?,
+            funds: vec![],
+        };
+        res.messages.push(SubMsg::new(exec_cw721_transfer));
+    }
+
+    res.messages
+        .append(&mut prepare_sale_hook(deps.as_ref(), &ask, buyer.clone())?);
+
+    let event = Event::new("finalize-sale")
+        .add_attribute("collection", ask.collection.to_string())
+        .add_attribute("token_id", ask.token_id.to_string())
+        .add_attribute("seller", ask.seller.to_string())
+        .add_attribute("buyer", buyer.to_string())
+        .add_attribute("price", price.to_string());
+    res.events.push(event);
+
+    Ok(())
+}
+/// Royalties owed on a sale at `price`. Royalties registered with the marketplace
+/// come first, then the sg721 collection royalty, then cw2981 royalties.
+/// Collections supporting none of them, like plain cw721 ones, pay no royalties.
+/// Whichever the source, royalties are capped at `max_royalty_percent` of the price.
+pub fn collection_royalties(
+    deps: Deps,
+    collection: &Addr,
+    token_id: TokenId,
+    price: &Coin,
+) -> StdResult<Vec<RoyaltyPayout>> {
+    let payouts = match ROYALTIES.may_load(deps.storage, collection)? {
+        Some(royalties) => share_payouts(royalties, price)?,
+        None => match sg721_royalty(deps, collection)? {
+            Some(royalty) => share_payouts(vec![royalty], price)?,
+            None => cw2981_royalty(deps, collection, token_id, price)?,
+        },
+    };
+
+    // collections pick their own royalty and registered ones may predate a lower cap,
+    // so payouts are cut off once the cap is used up
+    let mut remaining = price.amount * SUDO_PARAMS.load(deps.storage)?.max_royalty_percent;
+    Ok(payouts
+        .into_iter()
+        .filter_map(|mut payout| {
+            let amount = payout.amount.amount.min(remaining);
+            remaining -= amount;
+            payout.amount.amount = amount;
+            (!amount.is_zero()).then_some(payout)
+        })
+        .collect())
+}
+
+/// Amounts of `price` owed to each royalty share
+fn share_payouts(shares: Vec<RoyaltyShare>, price: &Coin) -> StdResult<Vec<RoyaltyPayout>> {
+    shares
+        .into_iter()
+        .map(|royalty| {
+            if royalty.share > Decimal::one() {
+                return Err(StdError::generic_err("Fees exceed payment"));
+            }
+            let amount = price
+                .amount
+                .multiply_ratio(royalty.share.atomics(), Decimal::one().atomics());
+            Ok(RoyaltyPayout {
+                recipient: royalty.recipient,
+                amount: coin(amount.u128(), &price.denom),
+            })
+        })
+        .collect()
+}
+
+/// Royalty set in the sg721 collection info, `None` if the collection isn't sg721
+fn sg721_royalty(deps: Deps, collection: &Addr) -> StdResult<Option<RoyaltyShare>> {
+    let res: StdResult<CollectionInfoResponse> = deps
+        .querier
+        .query_wasm_smart(collection, &Sg721QueryMsg::CollectionInfo {});
+
+    match res {
+        Ok(collection_info) => parse_royalties(collection_info.royalty_info)
+            .map(|royalty| {
+                Ok(RoyaltyShare {
+                    recipient: deps.api.addr_validate(&royalty.payment_address)?,
+                    share: royalty.share,
+                })
+            })
+            .transpose(),
+        // not supported by collection
+        Err(_) => Ok(None),
+    }
+}
+
+/// Royalty reported by a cw2981 collection, empty if the collection doesn't support it
+fn cw2981_royalty(
+    deps: Deps,
+    collection: &Addr,
+    token_id: TokenId,
+    price: &Coin,
+) -> StdResult<Vec<RoyaltyPayout>> {
+    let res: StdResult<RoyaltiesInfoResponse> = deps.querier.query_wasm_smart(
+        collection,
+        &Cw721QueryMsg::Extension {
+            msg: Cw2981QueryMsg::RoyaltyInfo {
+                token_id: token_id.to_string(),
+                sale_price: price.amount,
+            },
+        },
+    );
+
+    match res {
+        Ok(royalty) if !royalty.royalty_amount.is_zero() => Ok(vec![RoyaltyPayout {
+            recipient: deps.api.addr_validate(&royalty.address)?,
+            amount: coin(royalty.royalty_amount.u128(), &price.denom),
+        }]),
+        // no royalty, or not supported by collection
+        _ => Ok(vec![]),
+    }
+}
+
+/// Check royalties are non-zero
+fn parse_royalties(royalty_info: Option<RoyaltyInfoResponse>) -> Option<RoyaltyInfoResponse> {
+    match royalty_info {
+        Some(royalty) => {
+            if royalty.share.is_zero() {
+                return None;
+            }
+            Some(royalty)
+        }
+        None => None,
+    }
+}
+/// Trading fee for sales in `denom`, falling back to the global fee
+pub fn trading_fee_percent(store: &dyn Storage, denom: &str) -> StdResult<Decimal> {
+    let trading_fee_percent = match AssetParams::load(store, denom)?.trading_fee_percent {
+        Some(trading_fee_percent) => trading_fee_percent,
+        None => SUDO_PARAMS.load(store)?.trading_fee_percent,
+    };
+
+    Ok(trading_fee_percent)
+}
+
+/// Applies a fee given as a fraction of the amount, rounding down
+fn fee_amount(amount: Uint128, fee_percent: Decimal) -> StdResult<Uint128> {
+    if fee_percent > Decimal::one() {
+        return Err(StdError::generic_err("Fees exceed payment"));
+    }
+    Ok(amount * fee_percent)
+}
+
+/// Applies a fee in basis points to an amount, rounding down
+fn fee_bps_amount(amount: Uint128, fee_bps: u64) -> StdResult<Uint128> {
+    if fee_bps > MAX_FEE_BPS {
+        return Err(StdError::generic_err("Fees exceed payment"));
+    }
+    Ok(amount.multiply_ratio(fee_bps, MAX_FEE_BPS))
+}
+
+/// Splits a sale price into the network fee, royalty, finders fee and seller amount.
+/// Fees are rounded down, so any remainder goes to the seller.
+pub fn calculate_payout(
+    price: &Coin,
+    trading_fee_percent: Decimal,
+    royalties: Vec<RoyaltyPayout>,
+    finders_fee_bps: Option<u64>,
+) -> StdResult<PayoutBreakdown> {
+    let network_fee = fee_amount(price.amount, trading_fee_percent)?;
+    let finders_fee = fee_bps_amount(price.amount, finders_fee_bps.unwrap_or(0))?;
+    let mut fees = network_fee.checked_add(finders_fee)?;
+    for royalty in &royalties {
+        if royalty.amount.denom != price.denom {
+            return Err(StdError::generic_err("Royalty paid in a different denom"));
+        }
+        fees = fees.checked_add(royalty.amount.amount)?;
+    }
+
+    let seller_amount = price
+        .amount
+        .checked_sub(fees)
+        .map_err(|_| StdError::generic_err("Fees exceed payment"))?;
+
+    Ok(PayoutBreakdown {
+        network_fee: coin(network_fee.u128(), &price.denom),
+        royalties,
+        finders_fee: coin(finders_fee.u128(), &price.denom),
+        seller_amount: coin(seller_amount.u128(), &price.denom),
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn payout(
+    deps: Deps,
+    collection: Addr,
+    royalties: Vec<RoyaltyPayout>,
+    payment: Coin,
+    payment_recipient: Addr,
+    finder: Option<Addr>,
+    finders_fee_bps: Option<u64>,
+    res: &mut Response,
+) -> StdResult<()> {
+    let breakdown = calculate_payout(
+        &payment,
+        trading_fee_percent(deps.storage, &payment.denom)?,
+        royalties,
+        finder.as_ref().and(finders_fee_bps),
+    )?;
+
+    // Distribute the network fee in the asset of the sale
+    if !breakdown.network_fee.amount.is_zero() {
+        distribute_fee(deps.storage, breakdown.network_fee, res)?;
+    }
+
+    if let Some(finder) = finder {
+        if !breakdown.finders_fee.amount.is_zero() {
+            res.messages.push(SubMsg::new(transfer_msg(
+                deps.storage,
+                &finder,
+                breakdown.finders_fee,
+            )?));
+        }
+    }
+
+    // If token supports royalties, payout shares to each royalty recipient
+    for royalty in breakdown.royalties {
+        if !royalty.amount.amount.is_zero() {
+            res.messages.push(SubMsg::new(transfer_msg(
+                deps.storage,
+                &royalty.recipient,
+                royalty.amount.clone(),
+            )?));
+        }
+        let event = Event::new("royalty-payout")
+            .add_attribute("collection", collection.to_string())
+            .add_attribute("amount", royalty.amount.to_string())
+            .add_attribute("recipient", royalty.recipient.to_string());
+        res.events.push(event);
+    }
+
+    if !breakdown.seller_amount.amount.is_zero() {
+        res.messages.push(SubMsg::new(transfer_msg(
+            deps.storage,
+            &payment_recipient,
+            breakdown.seller_amount,
+        )?));
+    }
+
+    Ok(())
+}
+
+fn prepare_sale_hook(deps: Deps, ask: &Ask, buyer: Addr) -> StdResult<Vec<SubMsg>> {
+    let submsgs = SALE_HOOKS.prepare_hooks(deps.storage, |h| {
+        let msg = SaleHookMsg {
+            collection: ask.collection.to_string(),
+            token_id: ask.token_id,
+            price: coin(ask.price.u128(), &ask.denom),
+            seller: ask.seller.to_string(),
+            buyer: buyer.to_string(),
+        };
+        let execute = WasmMsg::Execute {
+            contract_addr: h.to_string(),
+            msg: msg.into_binary()?,
+            funds: vec![],
+        };
+        Ok(SubMsg::reply_on_error(execute, HookReply::Sale as u64))
+    })?;
+
+    Ok(submsgs)
+}
+
+fn prepare_bid_hook(deps: Deps, bid: &Bid, action: HookAction) -> StdResult<Vec<SubMsg>> {
+    let submsgs = BID_HOOKS.prepare_hooks(deps.storage, |h| {
+        let msg = BidHookMsg { bid: bid.clone() };
+        let execute = WasmMsg::Execute {
+            contract_addr: h.to_string(),
+            msg: msg.into_binary(action.clone())?,
+            funds: vec![],
+        };
+        Ok(SubMsg::reply_on_error(execute, HookReply::Bid as u64))
+    })?;
+
+    Ok(submsgs)
+}
+
+/// Seller can accept a bid which transfers funds as well as the token. The bid may or may not be associated with an ask.
+pub fn execute_accept_bid(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: Addr,
+    token_id: TokenId,
+    bidder: Addr,
+    finder: Option<Addr>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    only_owner(deps.as_ref(), &env, &info, &collection, token_id)?;
+    only_tradable(deps.as_ref(), &env.block, &collection)?;
+    let bid_key = bid_key(&collection, token_id, &bidder);
+    let ask_key = ask_key(&collection, token_id);
+
+    let bid = bids().load(deps.storage, bid_key.clone())?;
+    if bid.is_expired(&env.block) {
+        return Err(ContractError::BidExpired {});
+    }
+
+    let mut listing_fee_msgs = vec![];
+    if let Some(existing_ask) = asks().may_load(deps.storage, ask_key.clone())? {
+        only_closed_auction(deps.storage, &env.block, &existing_ask)?;
+        remove_ask(deps.storage, ask_key.clone())?;
+        listing_fee_msgs = release_listing_fee(deps.storage, &env.block, ask_key, None)?;
+    }
+
+    // Create a temporary Ask
+    let ask = Ask {
+        sale_type: SaleType::Auction,
+        collection: collection.clone(),
+        token_id,
+        price: bid.price,
+        denom: bid.denom.clone(),
+        expires_at: bid.expires_at,
+        is_active: true,
+        dutch_auction: None,
+        min_bid_increment_percent: None,
+        seller: info.sender.clone(),
+        funds_recipient: Some(info.sender),
+        reserve_for: None,
+        finders_fee_bps: bid.finders_fee_bps,
+    };
+
+    // Remove accepted bid
+    bids().remove(deps.storage, bid_key)?;
+
+    let mut res = Response::new().add_messages(listing_fee_msgs);
+
+    // Transfer funds and NFT
+    finalize_sale(
+        deps.branch(),
+        &env,
+        ask,
+        bid.price,
+        bidder.clone(),
+        finder,
+        &mut res,
+    )?;
+
+    let event = Event::new("accept-bid")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("bidder", bidder)
+        .add_attribute("price", bid.price.to_string());
+
+    Ok(res.add_event(event))
+}
+
+/// Place a collection bid (limit order) across an entire collection
+pub fn execute_set_collection_bid(
+    deps: DepsMut,
+    env: Env,
+    bidder: Addr,
+    payment: Coin,
+    collection: Addr,
+    finders_fee_bps: Option<u64>,
+    expires: Timestamp,
+) -> Result<Response, ContractError> {
+    let params = SUDO_PARAMS.load(deps.storage)?;
+    let Coin {
+        denom,
+        amount: price,
+    } = payment;
+    if price < AssetParams::load(deps.storage, &denom)?.min_price {
+        return Err(ContractError::PriceTooSmall(price));
+    }
+    params.bid_expiry.is_valid(&env.block, expires)?;
+    if let Some(finders_fee_bps) = finders_fee_bps {
+        if Decimal::from_ratio(finders_fee_bps, MAX_FEE_BPS) > params.max_finders_fee_percent {
+            return Err(ContractError::InvalidFindersFeeBps(finders_fee_bps));
+        }
+    }
+
+    let mut res = Response::new();
+
+    let key = collection_bid_key(&collection, &bidder);
+    if let Some(existing_bid) = collection_bids().may_load(deps.storage, key.clone())? {
+        collection_bids().remove(deps.storage, key.clone())?;
+        let refund_bidder_msg = transfer_msg(
+            deps.storage,
+            &bidder,
+            coin(existing_bid.price.u128(), existing_bid.denom),
+        )?;
+        res = res.add_message(refund_bidder_msg);
+    }
+
+    let collection_bid = CollectionBid {
+        collection: collection.clone(),
+        bidder: bidder.clone(),
+        price,
+        denom,
+        finders_fee_bps,
+        expires_at: expires,
+    };
+    collection_bids().save(deps.storage, key, &collection_bid)?;
+
+    let hook = prepare_collection_bid_hook(deps.as_ref(), &collection_bid, HookAction::Create)?;
+
+    let event = Event::new("set-collection-bid")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("bidder", bidder)
+        .add_attribute("bid_price", price.to_string())
+        .add_attribute("expires", expires.to_string());
+
+    Ok(res.add_submessages(hook).add_event(event))
+}
+
+/// Remove an existing collection bid (limit order) and refund the bidder
+pub fn execute_remove_collection_bid(
+    deps: DepsMut,
+    info: MessageInfo,
+    collection: Addr,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let bidder = info.sender;
+
+    let key = collection_bid_key(&collection, &bidder);
+    let collection_bid = collection_bids()
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::BidNotFound {})?;
+    collection_bids().remove(deps.storage, key)?;
+
+    let refund_bidder_msg = transfer_msg(
+        deps.storage,
+        &collection_bid.bidder,
+        coin(collection_bid.price.u128(), &collection_bid.denom),
+    )?;
+
+    let hook = prepare_collection_bid_hook(deps.as_ref(), &collection_bid, HookAction::Delete)?;
+
+    let event = Event::new("remove-collection-bid")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("bidder", bidder);
+
+    Ok(Response::new()
+        .add_message(refund_bidder_msg)
+        .add_submessages(hook)
+        .add_event(event))
+}
+
+/// Owner of an item in a collection can accept a collection bid which transfers funds as well as the token
+pub fn execute_accept_collection_bid(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: Addr,
+    token_id: TokenId,
+    bidder: Addr,
+    finder: Option<Addr>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    only_owner(deps.as_ref(), &env, &info, &collection, token_id)?;
+    only_tradable(deps.as_ref(), &env.block, &collection)?;
+    let bid_key = collection_bid_key(&collection, &bidder);
+    let ask_key = ask_key(&collection, token_id);
+
+    let bid = collection_bids()
+        .may_load(deps.storage, bid_key.clone())?
+        .ok_or(ContractError::BidNotFound {})?;
+    if bid.is_expired(&env.block) {
+        return Err(ContractError::BidExpired {});
+    }
+    collection_bids().remove(deps.storage, bid_key)?;
+
+    let mut listing_fee_msgs = vec![];
+    if let Some(existing_ask) = asks().may_load(deps.storage, ask_key.clone())? {
+        only_closed_auction(deps.storage, &env.block, &existing_ask)?;
+        remove_ask(deps.storage, ask_key.clone())?;
+        listing_fee_msgs = release_listing_fee(deps.storage, &env.block, ask_key, None)?;
+    }
+
+    // Create a temporary Ask
+    let ask = Ask {
+        sale_type: SaleType::Auction,
+        collection: collection.clone(),
+        token_id,
+        price: bid.price,
+        denom: bid.denom.clone(),
+        expires_at: bid.expires_at,
+        is_active: true,
+        dutch_auction: None,
+        min_bid_increment_percent: None,
+        seller: info.sender.clone(),
+        funds_recipient: Some(info.sender),
+        reserve_for: None,
+        finders_fee_bps: bid.finders_fee_bps,
+    };
+
+    let mut res = Response::new().add_messages(listing_fee_msgs);
+
+    // Transfer funds and NFT
+    finalize_sale(
+        deps.branch(),
+        &env,
+        ask,
+        bid.price,
+        bidder.clone(),
+        finder,
+        &mut res,
+    )?;
+
+    let hook = prepare_collection_bid_hook(deps.as_ref(), &bid, HookAction::Delete)?;
+
+    let event = Event::new("accept-collection-bid")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("bidder", bidder)
+        .add_attribute("price", bid.price.to_string());
+
+    Ok(res.add_submessages(hook).add_event(event))
+}
+
+fn prepare_collection_bid_hook(
+    deps: Deps,
+    collection_bid: &CollectionBid,
+    action: HookAction,
+) -> StdResult<Vec<SubMsg>> {
+    let submsgs = COLLECTION_BID_HOOKS.prepare_hooks(deps.storage, |h| {
+        let msg = CollectionBidHookMsg {
+            collection_bid: collection_bid.clone(),
+        };
+        let execute = WasmMsg::Execute {
+            contract_addr: h.to_string(),
+            msg: msg.into_binary(action.clone())?,
+            funds: vec![],
+        };
+        Ok(SubMsg::reply_on_error(
+            execute,
+            HookReply::CollectionBid as u64,
+        ))
+    })?;
+
+    Ok(submsgs)
+}
+
+fn only_owner(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    collection: &Addr,
+    token_id: u32,
+) -> Result<Addr, ContractError> {
+    let owner = token_owner(deps, env, collection, token_id)?;
+    if owner != info.sender {
+        return Err(ContractError::UnauthorizedOwner {});
+    }
+
+    Ok(owner)
+}
+
+/// Owner of a token. Natively minted tokens are listed under the marketplace
+/// address and tracked in `tokens()`, other collections are queried over cw721.
+fn token_owner(deps: Deps, env: &Env, collection: &Addr, token_id: TokenId) -> StdResult<Addr> {
+    if *collection == env.contract.address {
+        return Ok(tokens().load(deps.storage, token_id.into())?.owner);
+    }
+    let res = Cw721Contract::<Empty, Empty>(collection.clone(), PhantomData, PhantomData)
+        .owner_of(&deps.querier, token_id.to_string(), false)?;
+
+    deps.api.addr_validate(&res.owner)
+}
+
+/// Checks the seller of an ask still holds the NFT
+fn seller_owns(deps: Deps, env: &Env, ask: &Ask) -> bool {
+    match token_owner(deps, env, &ask.collection, ask.token_id) {
+        Ok(owner) => owner == ask.seller,
+        Err(_) => false,
+    }
+}
+
+/// Checks that the sender is a registered operator
+fn only_operator(store: &dyn Storage, info: &MessageInfo) -> Result<Addr, ContractError> {
+    let params = SUDO_PARAMS.load(store)?;
+    if !params.operators.contains(&info.sender) {
+        return Err(ContractError::UnauthorizedOperator {});
+    }
+
+    Ok(info.sender.clone())
+}
+
+/// Checks that the sender created the collection, as its sg721 creator,
+/// cw721 minter or contract admin
+fn only_collection_creator(
+    deps: Deps,
+    info: &MessageInfo,
+    collection: &Addr,
+) -> Result<(), ContractError> {
+    let collection_info: StdResult<CollectionInfoResponse> = deps
+        .querier
+        .query_wasm_smart(collection, &Sg721QueryMsg::CollectionInfo {});
+    if matches!(collection_info, Ok(res) if res.creator == info.sender) {
+        return Ok(());
+    }
+
+    let minter: StdResult<MinterResponse> = deps
+        .querier
+        .query_wasm_smart(collection, &Cw721QueryMsg::<Empty>::Minter {});
+    if matches!(minter, Ok(res) if res.minter.as_deref() == Some(info.sender.as_str())) {
+        return Ok(());
+    }
+
+    let contract_info = deps.querier.query_wasm_contract_info(collection);
+    if matches!(contract_info, Ok(res) if res.admin.as_deref() == Some(info.sender.as_str())) {
+        return Ok(());
+    }
+
+    Err(ContractError::UnauthorizedCreator {})
+}
+
+/// Checks that the collection is tradable
+fn only_tradable(deps: Deps, block: &BlockInfo, collection: &Addr) -> Result<bool, ContractError> {
+    let res: Result<CollectionInfoResponse, StdError> = deps
+        .querier
+        .query_wasm_smart(collection.clone(), &Sg721QueryMsg::CollectionInfo {});
+
+    match res {
+        Ok(collection_info) => match collection_info.start_trading_time {
+            Some(start_trading_time) => {
+                if start_trading_time > block.time {
+                    Err(ContractError::CollectionNotTradable {})
+                } else {
+                    Ok(true)
+                }
+            }
+            // not set by collection, so tradable
+            None => Ok(true),
+        },
+        // not supported by collection
+        Err(_) => Ok(true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::ExpiryRange;
+    use crate::msg::{
+        AskOffset, BidExpirationOffset, BidOffset, CollectionOffset, FeeDistributionMsg,
+        FeeShareMsg, SudoMsg,
+    };
+    use crate::query::{
+        query_ask_count, query_asks, query_asks_by_seller, query_asks_sorted_by_price, query_bid,
+        query_bids, query_bids_by_bidder, query_bids_by_bidder_sorted_by_expiration,
+        query_bids_sorted_by_price,
+    };
+    use crate::state::{FeeDistribution, FeeShare};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{attr, ContractResult, SystemError, SystemResult, WasmQuery};
+    use cosmwasm_std::{CanonicalAddr, OwnedDeps, RecoverPubkeyError, VerificationError};
+    use cw721::OwnerOfResponse;
+    use k256::ecdsa::signature::DigestSigner;
+    use k256::ecdsa::{Signature, SigningKey};
+    use sha2::{Digest, Sha256};
+    use std::marker::PhantomData;
+
+    const NATIVE_DENOM: &str = "ucmdx";
+
+    /// `MockApi` only humanizes addresses it canonicalized itself, so the 20 byte
+    /// addresses derived from signer public keys are rendered as hex instead.
+    #[derive(Default)]
+    struct SigningApi(MockApi);
+
+    impl Api for SigningApi {
+        fn addr_validate(&self, human: &str) -> StdResult<Addr> {
+            self.0.addr_validate(human)
+        }
+
+        fn addr_canonicalize(&self, human: &str) -> StdResult<CanonicalAddr> {
+            self.0.addr_canonicalize(human)
+        }
+
+        fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
+            if canonical.len() != 20 {
+                return self.0.addr_humanize(canonical);
+            }
+            let hex: String = canonical.iter().map(|b| format!("{b:02x}")).collect();
+            Ok(Addr::unchecked(format!("signer{hex}")))
+        }
+
+        fn secp256k1_verify(
+            &self,
+            message_hash: &[u8],
+            signature: &[u8],
+            public_key: &[u8],
+        ) -> Result<bool, VerificationError> {
+            self.0.secp256k1_verify(message_hash, signature, public_key)
+        }
+
+        fn secp256k1_recover_pubkey(
+            &self,
+            message_hash: &[u8],
+            signature: &[u8],
+            recovery_param: u8,
+        ) -> Result<Vec<u8>, RecoverPubkeyError> {
+            self.0
+                .secp256k1_recover_pubkey(message_hash, signature, recovery_param)
+        }
+
+        fn ed25519_verify(
+            &self,
+            message: &[u8],
+            signature: &[u8],
+            public_key: &[u8],
+        ) -> Result<bool, VerificationError> {
+            self.0.ed25519_verify(message, signature, public_key)
+        }
+
+        fn ed25519_batch_verify(
+            &self,
+            messages: &[&[u8]],
+            signatures: &[&[u8]],
+            public_keys: &[&[u8]],
+        ) -> Result<bool, VerificationError> {
+            self.0
+                .ed25519_batch_verify(messages, signatures, public_keys)
+        }
+
+        fn debug(&self, message: &str) {
+            self.0.debug(message)
+        }
+    }
+
+    fn signing_dependencies() -> OwnedDeps<MockStorage, SigningApi, MockQuerier> {
+        OwnedDeps {
+            storage: MockStorage::default(),
+            api: SigningApi::default(),
+            querier: MockQuerier::default(),
+            custom_query_type: PhantomData,
+        }
+    }
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32]).unwrap()
+    }
+
+    fn signer_pubkey(key: &SigningKey) -> Binary {
+        Binary::from(key.verifying_key().to_bytes().as_slice())
+    }
+
+    /// Signs `payload` the way `verify_signature` expects it
+    fn sign<T: Serialize>(key: &SigningKey, payload: &T) -> Binary {
+        let mut sign_bytes = MOCK_CONTRACT_ADDR.as_bytes().to_vec();
+        sign_bytes.extend(to_vec(payload).unwrap());
+        let signature: Signature = key.sign_digest(Sha256::new_with_prefix(sign_bytes));
+        Binary::from(signature.as_ref())
+    }
+
+    fn instantiate_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            name: "Comdex NFT".to_string(),
+            symbol: "CNFT".to_string(),
+            minter: "minter".to_string(),
+            denom: NATIVE_DENOM.to_string(),
+            trading_fee_bps: 200,
+            ask_expiry: ExpiryRange::new(24 * 60 * 60, 180 * 24 * 60 * 60),
+            bid_expiry: ExpiryRange::new(24 * 60 * 60, 180 * 24 * 60 * 60),
+            operators: vec!["operator".to_string()],
+            max_finders_fee_bps: 1000,
+            min_price: Uint128::from(5u128),
+            stale_bid_duration: Duration::Time(100),
+            bid_removal_reward_bps: 100,
+            listing_fee: Uint128::zero(),
+            auction_extension_window: 600,
+            auction_extension_duration: 600,
+            min_bid_increment_bps: 500,
+            listing_fee_destination: FeeDestination::Burn,
+            listing_fee_grace_period: 0,
+            fee_distribution: FeeDistributionMsg {
+                shares: vec![],
+                burn_bps: 10000,
+            },
+            max_royalty_bps: 1000,
+        }
+    }
+
+    #[test]
+    fn proper_initialization() {
+        let mut deps = mock_dependencies();
+        let info = mock_info("creator", &[]);
+
+        let res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg()).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let params = SUDO_PARAMS.load(&deps.storage).unwrap();
+        assert_eq!(params.trading_fee_percent, Decimal::percent(2));
+        assert_eq!(params.operators, vec![Addr::unchecked("operator")]);
+        assert_eq!(DENOM.load(&deps.storage).unwrap(), NATIVE_DENOM);
+    }
+
+    fn setup_ask(deps: DepsMut, seller: &str, token_id: TokenId) -> Ask {
+        let ask = Ask {
+            sale_type: SaleType::FixedPrice,
+            collection: Addr::unchecked("collection"),
+            token_id,
+            seller: Addr::unchecked(seller),
+            price: Uint128::from(100u128),
+            denom: NATIVE_DENOM.to_string(),
+            funds_recipient: None,
+            reserve_for: None,
+            finders_fee_bps: None,
+            expires_at: mock_env().block.time.plus_seconds(24 * 60 * 60 + 1),
+            is_active: true,
+            dutch_auction: None,
+            min_bid_increment_percent: None,
+        };
+        store_ask(deps.storage, &ask).unwrap();
+        ask
+    }
+
+    #[test]
+    fn try_remove_ask() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+
+        let err = execute_remove_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("seller", &[]),
+            collection.clone(),
+            1,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AskNotFound {});
+
+        setup_ask(deps.as_mut(), "seller", 1);
+        let err = execute_remove_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            collection.clone(),
+            1,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute_remove_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("seller", &[]),
+            collection.clone(),
+            1,
+        )
+        .unwrap();
+        assert!(asks()
+            .may_load(&deps.storage, ask_key(&collection, 1))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn try_update_ask_price() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        setup_ask(deps.as_mut(), "seller", 1);
+        setup_ask(deps.as_mut(), "seller", 2);
+
+        let err = execute_update_ask_price(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("seller", &[]),
+            collection.clone(),
+            1,
+            coin(100, NATIVE_DENOM),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AskUnchanged {});
+
+        execute_update_ask_price(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("seller", &[]),
+            collection.clone(),
+            1,
+            coin(150, NATIVE_DENOM),
+        )
+        .unwrap();
+
+        let res = crate::query::query_asks_sorted_by_price(
+            deps.as_ref(),
+            collection,
+            None,
+            None,
+            None,
+            Some(true),
+        )
+        .unwrap();
+        let prices: Vec<_> = res.asks.iter().map(|ask| ask.price.u128()).collect();
+        assert_eq!(prices, vec![150, 100]);
+    }
+
+    #[test]
+    fn try_remove_stale_asks() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        setup_ask(deps.as_mut(), "seller", 1);
+        let mut ask = setup_ask(deps.as_mut(), "seller", 2);
+        ask.expires_at = mock_env().block.time.plus_seconds(10 * 24 * 60 * 60);
+        store_ask(deps.as_mut().storage, &ask).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2 * 24 * 60 * 60);
+
+        let err = execute_remove_stale_asks(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            collection.clone(),
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::UnauthorizedOperator {});
+
+        let err = execute_remove_stale_ask(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("operator", &[]),
+            collection.clone(),
+            2,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AskNotExpired {});
+
+        let res = execute_remove_stale_asks(
+            deps.as_mut(),
+            env,
+            mock_info("operator", &[]),
+            collection.clone(),
+            None,
+            None,
+        )
+        .unwrap();
+        let removed = res.events[0]
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "removed")
+            .unwrap();
+        assert_eq!(removed.value, "1");
+        assert!(asks()
+            .may_load(&deps.storage, ask_key(&collection, 1))
+            .unwrap()
+            .is_none());
+        assert!(asks()
+            .may_load(&deps.storage, ask_key(&collection, 2))
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn try_remove_stale_bid() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        let bidder = Addr::unchecked("bidder");
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let bid = Bid::new(
+            collection.clone(),
+            1,
+            bidder.clone(),
+            coin(100, NATIVE_DENOM),
+            None,
+            mock_env().block.time.plus_seconds(10),
+        );
+        store_bid(deps.as_mut().storage, &bid).unwrap();
+
+        // expired, but not yet stale
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(20);
+        let err = execute_remove_stale_bid(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("operator", &[]),
+            collection.clone(),
+            1,
+            bidder.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::BidNotStale {});
+
+        env.block.time = env.block.time.plus_seconds(100);
+        let res = execute_remove_stale_bid(
+            deps.as_mut(),
+            env,
+            mock_info("operator", &[]),
+            collection.clone(),
+            1,
+            bidder.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: bidder.to_string(),
+                amount: vec![coin(99, NATIVE_DENOM)],
+            }
+            .into()
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            BankMsg::Send {
+                to_address: "operator".to_string(),
+                amount: vec![coin(1, NATIVE_DENOM)],
+            }
+            .into()
+        );
+        assert!(bids()
+            .may_load(&deps.storage, bid_key(&collection, 1, &bidder))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn try_set_and_remove_collection_bid() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let expires = mock_env().block.time.plus_seconds(2 * 24 * 60 * 60);
+
+        execute_set_collection_bid(
+            deps.as_mut(),
+            mock_env(),
+            Addr::unchecked("bidder"),
+            coin(100, NATIVE_DENOM),
+            collection.clone(),
+            None,
+            expires,
+        )
+        .unwrap();
+
+        // placing a new bid refunds the previous one
+        let res = execute_set_collection_bid(
+            deps.as_mut(),
+            mock_env(),
+            Addr::unchecked("bidder"),
+            coin(150, NATIVE_DENOM),
+            collection.clone(),
+            None,
+            expires,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: "bidder".to_string(),
+                amount: vec![coin(100, NATIVE_DENOM)],
+            }
+            .into()
+        );
+
+        let res = execute_remove_collection_bid(
+            deps.as_mut(),
+            mock_info("bidder", &[]),
+            collection.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: "bidder".to_string(),
+                amount: vec![coin(150, NATIVE_DENOM)],
+            }
+            .into()
+        );
+
+        let err =
+            execute_remove_collection_bid(deps.as_mut(), mock_info("bidder", &[]), collection)
+                .unwrap_err();
+        assert_eq!(err, ContractError::BidNotFound {});
+    }
+
+    #[test]
+    fn try_mint() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let msg = ExecuteMsg::Mint {
+            owner: "owner".to_string(),
+            token_uri: Some("ipfs://token".to_string()),
+            base_price: vec![coin(100, NATIVE_DENOM)],
+        };
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(deps.as_mut(), mock_env(), mock_info("minter", &[]), msg).unwrap();
+
+        let token = crate::query::query_token(deps.as_ref(), 1).unwrap();
+        assert_eq!(token.owner, Addr::unchecked("owner"));
+        let res = crate::query::query_tokens(deps.as_ref(), Addr::unchecked("owner"), None, None)
+            .unwrap();
+        assert_eq!(res.tokens, vec![token]);
+        let res = crate::query::query_num_tokens(deps.as_ref()).unwrap();
+        assert_eq!(res.count, 1);
+    }
+
+    #[test]
+    fn try_mint_and_list() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let msg = ExecuteMsg::MintAndList {
+            token_uri: None,
+            base_price: vec![coin(100, NATIVE_DENOM)],
+            sale_type: SaleType::FixedPrice,
+            price: None,
+            funds_recipient: None,
+            reserve_for: None,
+            finders_fee_bps: None,
+            expires: mock_env().block.time.plus_seconds(2 * 24 * 60 * 60),
+        };
+
+        // a Dutch auction needs its price curve, which only SetDutchAuction takes
+        let dutch_msg = ExecuteMsg::MintAndList {
+            token_uri: None,
+            base_price: vec![coin(100, NATIVE_DENOM)],
+            sale_type: SaleType::DutchAuction,
+            price: None,
+            funds_recipient: None,
+            reserve_for: None,
+            finders_fee_bps: None,
+            expires: mock_env().block.time.plus_seconds(2 * 24 * 60 * 60),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minter", &[]),
+            dutch_msg,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidDutchAuction(_)));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("minter", &[]), msg).unwrap();
+        assert!(res.attributes.iter().any(|attr| attr.value == "mint"));
+        assert_eq!(res.events[0].ty, "set-ask");
+
+        let ask = asks()
+            .load(&deps.storage, ask_key(&mock_env().contract.address, 1))
+            .unwrap();
+        assert_eq!(ask.seller, Addr::unchecked("minter"));
+        assert_eq!(ask.price, Uint128::from(100u128));
+        assert_eq!(
+            crate::query::query_token(deps.as_ref(), 1).unwrap().owner,
+            Addr::unchecked("minter")
+        );
+
+        // buying moves the native token to the buyer instead of sending a cw721 transfer
+        let buy_msg = ExecuteMsg::BuyNow {
+            collection: mock_env().contract.address.to_string(),
+            token_id: 1,
+            expires: mock_env().block.time.plus_seconds(2 * 24 * 60 * 60),
+            finder: None,
+            finders_fee_bps: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[coin(100, NATIVE_DENOM)]),
+            buy_msg,
+        )
+        .unwrap();
+        assert_eq!(
+            crate::query::query_token(deps.as_ref(), 1).unwrap().owner,
+            Addr::unchecked("buyer")
+        );
+        assert!(!asks().has(&deps.storage, ask_key(&mock_env().contract.address, 1)));
+        assert!(res
+            .messages
+            .iter()
+            .all(|msg| !matches!(msg.msg, CosmosMsg::Wasm(_))));
+        assert!(res.messages.iter().any(|msg| msg.msg
+            == BankMsg::Send {
+                to_address: "minter".to_string(),
+                amount: vec![coin(98, NATIVE_DENOM)],
+            }
+            .into()));
+        assert!(res.events.iter().any(|event| event.ty == "finalize-sale"));
+    }
+
+    #[test]
+    fn try_mint_and_list_token_id_range() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let mut config = CONFIG.load(&deps.storage).unwrap();
+        config.num_tokens = u32::MAX as u64;
+        CONFIG.save(&mut deps.storage, &config).unwrap();
+
+        let msg = ExecuteMsg::MintAndList {
+            token_uri: None,
+            base_price: vec![coin(100, NATIVE_DENOM)],
+            sale_type: SaleType::FixedPrice,
+            price: None,
+            funds_recipient: None,
+            reserve_for: None,
+            finders_fee_bps: None,
+            expires: mock_env().block.time.plus_seconds(2 * 24 * 60 * 60),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("minter", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::TokenIdOutOfRange(u32::MAX as u64 + 1));
+    }
+
+    #[test]
+    fn try_redeem_invalid_voucher() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let mut voucher = MintVoucher {
+            token_uri: None,
+            price: coin(100, NATIVE_DENOM),
+            creator_pubkey: Binary::from(vec![2u8; 33]),
+            nonce: 1,
+            expires: mock_env().block.time,
+        };
+
+        let err = execute_redeem_voucher(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[coin(100, NATIVE_DENOM)]),
+            voucher.clone(),
+            Binary::from(vec![0u8; 64]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::VoucherExpired {});
+
+        voucher.expires = mock_env().block.time.plus_seconds(60);
+
+        // cw20 tokens can't be sent along with the redeem message
+        crate::sudo::sudo_add_payment_asset(
+            deps.as_mut(),
+            "cw20token".to_string(),
+            AssetType::Cw20,
+        )
+        .unwrap();
+        let mut cw20_voucher = voucher.clone();
+        cw20_voucher.price = coin(100, "cw20token");
+        let err = execute_redeem_voucher(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[]),
+            cw20_voucher,
+            Binary::from(vec![0u8; 64]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NativeDenomRequired("cw20token".to_string())
+        );
+
+        let err = execute_redeem_voucher(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[coin(100, NATIVE_DENOM)]),
+            voucher,
+            Binary::from(vec![0u8; 64]),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidSignature {} | ContractError::Verification(_)
+        ));
+    }
+
+    #[test]
+    fn try_settle_auction() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+
+        let err = execute_settle_auction(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            collection.clone(),
+            1,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AskNotFound {});
+
+        let mut ask = setup_ask(deps.as_mut(), "seller", 1);
+        let err = execute_settle_auction(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            collection.clone(),
+            1,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotAuction {});
+
+        ask.sale_type = SaleType::Auction;
+        store_ask(deps.as_mut().storage, &ask).unwrap();
+        let err = execute_settle_auction(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            collection.clone(),
+            1,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AuctionNotEnded {});
+
+        // an auction without bids closes without a sale
+        let mut env = mock_env();
+        env.block.time = ask.expires_at;
+        let res = execute_settle_auction(
+            deps.as_mut(),
+            env,
+            mock_info("anyone", &[]),
+            collection.clone(),
+            1,
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(res.events[0].attributes[3].value, "no_sale");
+        assert!(asks()
+            .may_load(deps.as_ref().storage, ask_key(&collection, 1))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn try_extend_auction() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+
+        let mut ask = setup_ask(deps.as_mut(), "seller", 1);
+        ask.sale_type = SaleType::Auction;
+        store_ask(deps.as_mut().storage, &ask).unwrap();
+
+        let bid_info = |env: &Env| BidInfo {
+            collection: collection.clone(),
+            token_id: 1,
+            expires: env.block.time.plus_seconds(2 * 24 * 60 * 60),
+            finder: None,
+            finders_fee_bps: None,
+        };
+
+        // a bid well before the end leaves the auction untouched
+        let env = mock_env();
+        execute_set_bid(
+            deps.as_mut(),
+            env.clone(),
+            Addr::unchecked("bidder1"),
+            coin(100, NATIVE_DENOM),
+            SaleType::Auction,
+            bid_info(&env),
+            false,
+        )
+        .unwrap();
+        let stored = asks()
+            .load(deps.as_ref().storage, ask_key(&collection, 1))
+            .unwrap();
+        assert_eq!(stored.expires_at, ask.expires_at);
+
+        // a bid inside the extension window pushes the end forward and refunds the outbid bidder
+        let mut env = mock_env();
+        env.block.time = ask.expires_at.minus_seconds(60);
+        let res = execute_set_bid(
+            deps.as_mut(),
+            env.clone(),
+            Addr::unchecked("bidder2"),
+            coin(150, NATIVE_DENOM),
+            SaleType::Auction,
+            bid_info(&env),
+            false,
+        )
+        .unwrap();
+        let stored = asks()
+            .load(deps.as_ref().storage, ask_key(&collection, 1))
+            .unwrap();
+        assert_eq!(stored.expires_at, env.block.time.plus_seconds(600));
+        assert!(res.events.iter().any(|e| e.ty == "auction-extended"));
+        assert_eq!(res.messages.len(), 1);
+
+        // outbidding by less than the min increment is rejected
+        let err = execute_set_bid(
+            deps.as_mut(),
+            env.clone(),
+            Addr::unchecked("bidder1"),
+            coin(155, NATIVE_DENOM),
+            SaleType::Auction,
+            bid_info(&env),
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BidIncrementTooSmall(Uint128::from(157u128))
+        );
+    }
+
+    #[test]
+    fn try_dutch_auction_price() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+
+        let start_time = mock_env().block.time;
+        let mut ask = setup_ask(deps.as_mut(), "seller", 1);
+        ask.sale_type = SaleType::DutchAuction;
+        ask.price = Uint128::from(1000u128);
+        ask.dutch_auction = Some(DutchAuction {
+            start_price: Uint128::from(1000u128),
+            end_price: Uint128::from(200u128),
+            start_time,
+            end_time: start_time.plus_seconds(1000),
+        });
+        store_ask(deps.as_mut().storage, &ask).unwrap();
+
+        let current_price = |seconds: u64| {
+            let mut env = mock_env();
+            env.block.time = start_time.plus_seconds(seconds);
+            crate::query::query_ask_current_price(deps.as_ref(), env, collection.clone(), 1)
+                .unwrap()
+                .price
+                .unwrap()
+        };
+        assert_eq!(current_price(0), coin(1000, NATIVE_DENOM));
+        assert_eq!(current_price(250), coin(800, NATIVE_DENOM));
+        assert_eq!(current_price(1000), coin(200, NATIVE_DENOM));
+        assert_eq!(current_price(5000), coin(200, NATIVE_DENOM));
+
+        let err = execute_update_ask_price(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("seller", &[]),
+            collection,
+            1,
+            coin(500, NATIVE_DENOM),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidDutchAuction(_)));
+    }
+
+    #[test]
+    fn try_cw20_collection_bid() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+
+        let receive_msg = Cw20ReceiveMsg {
+            sender: "bidder".to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&ReceiveMsg::SetCollectionBid {
+                collection: collection.to_string(),
+                finders_fee_bps: None,
+                expires: mock_env().block.time.plus_seconds(2 * 24 * 60 * 60),
+            })
+            .unwrap(),
+        };
+
+        // tokens must be registered as a payment asset
+        let err = execute_receive(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("cw20token", &[]),
+            receive_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidDenom("cw20token".to_string()));
+
+        crate::sudo::sudo_add_payment_asset(
+            deps.as_mut(),
+            "cw20token".to_string(),
+            AssetType::Cw20,
+        )
+        .unwrap();
+        execute_receive(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("cw20token", &[]),
+            receive_msg,
+        )
+        .unwrap();
+        let bid = collection_bids()
+            .load(
+                deps.as_ref().storage,
+                collection_bid_key(&collection, &Addr::unchecked("bidder")),
+            )
+            .unwrap();
+        assert_eq!(bid.denom, "cw20token");
+
+        // the refund is paid back in the same token, even after it was disabled
+        crate::sudo::sudo_remove_payment_asset(deps.as_mut(), "cw20token".to_string()).unwrap();
+        let res =
+            execute_remove_collection_bid(deps.as_mut(), mock_info("bidder", &[]), collection)
+                .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "cw20token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "bidder".to_string(),
+                    amount: Uint128::from(100u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn try_asset_params() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+
+        let err = crate::sudo::sudo_set_asset_params(
+            deps.as_mut(),
+            "unknown".to_string(),
+            Uint128::from(50u128),
+            Uint128::zero(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::PaymentAssetNotRegistered {});
+
+        // falls back to the global min price
+        price_validate(deps.as_ref().storage, &coin(10, NATIVE_DENOM)).unwrap();
+
+        crate::sudo::sudo_set_asset_params(
+            deps.as_mut(),
+            NATIVE_DENOM.to_string(),
+            Uint128::from(50u128),
+            Uint128::zero(),
+            Some(100),
+        )
+        .unwrap();
+        let err = price_validate(deps.as_ref().storage, &coin(10, NATIVE_DENOM)).unwrap_err();
+        assert_eq!(err, ContractError::PriceTooSmall(Uint128::from(10u128)));
+
+        let res = crate::query::query_asset_params(deps.as_ref(), None, None).unwrap();
+        assert_eq!(res.params.len(), 1);
+        assert_eq!(
+            res.params[0].1.trading_fee_percent,
+            Some(Decimal::percent(1))
+        );
+
+        // asks priced in cw20 tokens pay the global listing fee, so theirs can't be set
+        crate::sudo::sudo_add_payment_asset(
+            deps.as_mut(),
+            "cw20token".to_string(),
+            AssetType::Cw20,
+        )
+        .unwrap();
+        let err = crate::sudo::sudo_set_asset_params(
+            deps.as_mut(),
+            "cw20token".to_string(),
+            Uint128::from(50u128),
+            Uint128::from(10u128),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidListingFee(Uint128::from(10u128)));
+
+        crate::sudo::sudo_remove_asset_params(deps.as_mut(), NATIVE_DENOM.to_string()).unwrap();
+        price_validate(deps.as_ref().storage, &coin(10, NATIVE_DENOM)).unwrap();
+        let err = crate::sudo::sudo_remove_asset_params(deps.as_mut(), NATIVE_DENOM.to_string())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AssetParamsNotFound(NATIVE_DENOM.to_string())
+        );
+    }
+
+    #[test]
+    fn try_listing_fee() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        let seller = Addr::unchecked("seller");
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let mut params = SUDO_PARAMS.load(deps.as_ref().storage).unwrap();
+        params.listing_fee = Uint128::from(10u128);
+        params.listing_fee_grace_period = 100;
+        SUDO_PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        let block = mock_env().block;
+        let key = ask_key(&collection, 1);
+        let err = charge_listing_fee(
+            deps.as_mut().storage,
+            &mock_info("seller", &[coin(5, NATIVE_DENOM)]),
+            &block,
+            key.clone(),
+            NATIVE_DENOM,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidListingFee(Uint128::from(5u128)));
+
+        // cancelling within the grace period refunds the fee
+        let msgs = charge_listing_fee(
+            deps.as_mut().storage,
+            &mock_info("seller", &[coin(10, NATIVE_DENOM)]),
+            &block,
+            key.clone(),
+            NATIVE_DENOM,
+        )
+        .unwrap();
+        assert!(msgs.is_empty());
+        let msgs =
+            release_listing_fee(deps.as_mut().storage, &block, key.clone(), Some(&seller)).unwrap();
+        assert_eq!(
+            msgs,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: seller.to_string(),
+                amount: vec![coin(10, NATIVE_DENOM)],
+            })]
+        );
+
+        // after the grace period the fee goes to the destination
+        charge_listing_fee(
+            deps.as_mut().storage,
+            &mock_info("seller", &[coin(10, NATIVE_DENOM)]),
+            &block,
+            key.clone(),
+            NATIVE_DENOM,
+        )
+        .unwrap();
+        let mut later = mock_env().block;
+        later.time = later.time.plus_seconds(100);
+        let msgs =
+            release_listing_fee(deps.as_mut().storage, &later, key.clone(), Some(&seller)).unwrap();
+        assert_eq!(
+            msgs,
+            vec![CosmosMsg::Bank(BankMsg::Burn {
+                amount: vec![coin(10, NATIVE_DENOM)],
+            })]
+        );
+
+        // a new owner listing the token refunds the previous seller's fee in full
+        setup_ask(deps.as_mut(), "seller", 1);
+        charge_listing_fee(
+            deps.as_mut().storage,
+            &mock_info("seller", &[coin(10, NATIVE_DENOM)]),
+            &block,
+            key.clone(),
+            NATIVE_DENOM,
+        )
+        .unwrap();
+        let msgs = charge_listing_fee(
+            deps.as_mut().storage,
+            &mock_info("new_owner", &[coin(10, NATIVE_DENOM)]),
+            &later,
+            key.clone(),
+            NATIVE_DENOM,
+        )
+        .unwrap();
+        assert_eq!(
+            msgs,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: seller.to_string(),
+                amount: vec![coin(10, NATIVE_DENOM)],
+            })]
+        );
+        assert_eq!(
+            LISTING_FEES.load(deps.as_ref().storage, key).unwrap().fee,
+            coin(10, NATIVE_DENOM)
+        );
+    }
+
+    #[test]
+    fn try_list_not_owner() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let token_id = mint_token(
+            deps.as_mut().storage,
+            Addr::unchecked("owner"),
+            None,
+            vec![],
+        )
+        .unwrap();
+        let collection = Addr::unchecked(MOCK_CONTRACT_ADDR);
+
+        let err = execute_set_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other", &[]),
+            NFTinfo {
+                sale_type: SaleType::FixedPrice,
+                collection: collection.clone(),
+                token_id: token_id as TokenId,
+                price: coin(100, NATIVE_DENOM),
+                funds_recipient: None,
+                reserve_for: None,
+                finders_fee_bps: None,
+                expires: mock_env().block.time.plus_seconds(2 * 24 * 60 * 60),
+                reserve_price: None,
+                min_bid_increment_bps: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::UnauthorizedOwner {});
+
+        let err = execute_set_dutch_auction(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other", &[]),
+            DutchAuctionInfo {
+                collection,
+                token_id: token_id as TokenId,
+                start_price: coin(200, NATIVE_DENOM),
+                end_price: coin(100, NATIVE_DENOM),
+                start_time: mock_env().block.time,
+                end_time: mock_env().block.time.plus_seconds(24 * 60 * 60),
+                funds_recipient: None,
+                finders_fee_bps: None,
+                expires: mock_env().block.time.plus_seconds(2 * 24 * 60 * 60),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::UnauthorizedOwner {});
+    }
+
+    #[test]
+    fn try_fee_distribution() {
+        let mut deps = mock_dependencies();
+        let mut msg = instantiate_msg();
+        msg.fee_distribution = FeeDistributionMsg {
+            shares: vec![FeeShareMsg {
+                recipient: "treasury".to_string(),
+                share_bps: 5000,
+            }],
+            burn_bps: 4000,
+        };
+        let err = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidFeeDistribution {});
+
+        msg.fee_distribution = FeeDistributionMsg {
+            shares: vec![
+                FeeShareMsg {
+                    recipient: "treasury".to_string(),
+                    share_bps: 5000,
+                },
+                FeeShareMsg {
+                    recipient: "treasury".to_string(),
+                    share_bps: 5000,
+                },
+            ],
+            burn_bps: 0,
+        };
+        let err = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::DuplicateRecipient("treasury".to_string())
+        );
+
+        msg.fee_distribution = FeeDistributionMsg {
+            shares: vec![
+                FeeShareMsg {
+                    recipient: "treasury".to_string(),
+                    share_bps: 3333,
+                },
+                FeeShareMsg {
+                    recipient: "stakers".to_string(),
+                    share_bps: 6667,
+                },
+            ],
+            burn_bps: 0,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // rounding dust goes to the first recipient when nothing is burned
+        let mut res = Response::new();
+        distribute_fee(deps.as_ref().storage, coin(10, NATIVE_DENOM), &mut res).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: "treasury".to_string(),
+                    amount: vec![coin(4, NATIVE_DENOM)],
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "stakers".to_string(),
+                    amount: vec![coin(6, NATIVE_DENOM)],
+                }),
+            ]
+        );
+
+        let res = crate::sudo::sudo_update_fee_distribution(
+            deps.as_mut(),
+            FeeDistribution {
+                shares: vec![FeeShare {
+                    recipient: Addr::unchecked("treasury"),
+                    share_bps: 5000,
+                }],
+                burn_bps: 5000,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.events[0].attributes,
+            [
+                attr("burn_bps", "5000"),
+                attr("recipient", "treasury"),
+                attr("share_bps", "5000"),
+            ]
+        );
+        let mut res = Response::new();
+        distribute_fee(deps.as_ref().storage, coin(11, NATIVE_DENOM), &mut res).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: "treasury".to_string(),
+                    amount: vec![coin(5, NATIVE_DENOM)],
+                }),
+                SubMsg::new(BankMsg::Burn {
+                    amount: vec![coin(6, NATIVE_DENOM)],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn try_calculate_payout() {
+        let royalties = vec![
+            RoyaltyPayout {
+                recipient: Addr::unchecked("creator"),
+                amount: coin(30, NATIVE_DENOM),
+            },
+            RoyaltyPayout {
+                recipient: Addr::unchecked("artist"),
+                amount: coin(20, NATIVE_DENOM),
+            },
+        ];
+
+        // 2% network fee, 5% royalties and a 1.25% finders fee
+        let payout = calculate_payout(
+            &coin(1001, NATIVE_DENOM),
+            Decimal::percent(2),
+            royalties.clone(),
+            Some(125),
+        )
+        .unwrap();
+        assert_eq!(
+            payout,
+            PayoutBreakdown {
+                network_fee: coin(20, NATIVE_DENOM),
+                royalties: royalties.clone(),
+                finders_fee: coin(12, NATIVE_DENOM),
+                seller_amount: coin(919, NATIVE_DENOM),
+            }
+        );
+
+        // fees that add up to more than the price are rejected instead of underflowing
+        let err = calculate_payout(
+            &coin(1000, NATIVE_DENOM),
+            Decimal::percent(50),
+            royalties,
+            Some(5000),
+        )
+        .unwrap_err();
+        assert_eq!(err, StdError::generic_err("Fees exceed payment"));
+    }
+
+    #[test]
+    fn try_set_royalties() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { msg, .. }
+                if from_binary::<Cw721QueryMsg<Empty>>(msg) == Ok(Cw721QueryMsg::Minter {}) =>
+            {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&MinterResponse {
+                        minter: Some("creator".to_string()),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "collection".to_string(),
+            }),
+        });
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+
+        let royalties = vec![
+            RoyaltyShareMsg {
+                recipient: "creator".to_string(),
+                share_bps: 300,
+            },
+            RoyaltyShareMsg {
+                recipient: "artist".to_string(),
+                share_bps: 200,
+            },
+        ];
+        let err = execute_set_royalties(
+            deps.as_mut(),
+            mock_info("anyone", &[]),
+            collection.clone(),
+            royalties.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::UnauthorizedCreator {});
+
+        // royalties are capped by the max royalty
+        let mut too_high = royalties.clone();
+        too_high[1].share_bps = 800;
+        let err = execute_set_royalties(
+            deps.as_mut(),
+            mock_info("creator", &[]),
+            collection.clone(),
+            too_high,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidRoyalties {});
+
+        let mut duplicate = royalties.clone();
+        duplicate[1].recipient = "creator".to_string();
+        let err = execute_set_royalties(
+            deps.as_mut(),
+            mock_info("creator", &[]),
+            collection.clone(),
+            duplicate,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::DuplicateRecipient("creator".to_string())
+        );
+
+        let res = execute_set_royalties(
+            deps.as_mut(),
+            mock_info("creator", &[]),
+            collection.clone(),
+            royalties,
+        )
+        .unwrap();
+        assert_eq!(
+            res.events[0].attributes[1..],
+            [
+                attr("recipient", "creator"),
+                attr("share_bps", "300"),
+                attr("recipient", "artist"),
+                attr("share_bps", "200"),
+            ]
+        );
+        let royalties =
+            collection_royalties(deps.as_ref(), &collection, 1, &coin(1000, NATIVE_DENOM)).unwrap();
+        assert_eq!(royalties.len(), 2);
+        assert_eq!(royalties[1].amount, coin(20, NATIVE_DENOM));
+
+        let mut res = Response::new();
+        payout(
+            deps.as_ref(),
+            collection.clone(),
+            royalties,
+            coin(1000, NATIVE_DENOM),
+            Addr::unchecked("seller"),
+            None,
+            None,
+            &mut res,
+        )
+        .unwrap();
+        let royalty_events: Vec<&Event> = res
+            .events
+            .iter()
+            .filter(|event| event.ty == "royalty-payout")
+            .collect();
+        assert_eq!(royalty_events.len(), 2);
+
+        // an empty list removes the registered royalties
+        execute_set_royalties(
+            deps.as_mut(),
+            mock_info("creator", &[]),
+            collection.clone(),
+            vec![],
+        )
+        .unwrap();
+        let res = crate::query::query_royalties(deps.as_ref(), collection).unwrap();
+        assert!(res.royalties.is_empty());
+    }
+
+    #[test]
+    fn try_collection_royalties() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        let price = coin(1000, NATIVE_DENOM);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+
+        // sg721 collections set a royalty share in their collection info
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { msg, .. }
+                if from_binary::<Sg721QueryMsg>(msg) == Ok(Sg721QueryMsg::CollectionInfo {}) =>
+            {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&CollectionInfoResponse {
+                        creator: "creator".to_string(),
+                        description: "description".to_string(),
+                        image: "https://example.com/image.png".to_string(),
+                        external_link: None,
+                        explicit_content: None,
+                        start_trading_time: None,
+                        royalty_info: Some(RoyaltyInfoResponse {
+                            payment_address: "creator".to_string(),
+                            share: Decimal::percent(5),
+                        }),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "sg721".to_string(),
+            }),
+        });
+        let royalties = collection_royalties(deps.as_ref(), &collection, 1, &price).unwrap();
+        assert_eq!(
+            royalties,
+            vec![RoyaltyPayout {
+                recipient: Addr::unchecked("creator"),
+                amount: coin(50, NATIVE_DENOM),
+            }]
+        );
+
+        // the sg721 share is held to the max royalty as well
+        let mut params = SUDO_PARAMS.load(deps.as_ref().storage).unwrap();
+        params.max_royalty_percent = Decimal::percent(2);
+        SUDO_PARAMS.save(deps.as_mut().storage, &params).unwrap();
+        let royalties = collection_royalties(deps.as_ref(), &collection, 1, &price).unwrap();
+        assert_eq!(royalties[0].amount, coin(20, NATIVE_DENOM));
+        params.max_royalty_percent = Decimal::percent(10);
+        SUDO_PARAMS.save(deps.as_mut().storage, &params).unwrap();
+
+        // cw2981 collections report a royalty amount for the sale price
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { msg, .. }
+                if from_binary::<Cw721QueryMsg<Cw2981QueryMsg>>(msg).is_ok() =>
+            {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&RoyaltiesInfoResponse {
+                        address: "artist".to_string(),
+                        royalty_amount: Uint128::from(70u128),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "cw2981".to_string(),
+            }),
+        });
+        let royalties = collection_royalties(deps.as_ref(), &collection, 1, &price).unwrap();
+        assert_eq!(
+            royalties,
+            vec![RoyaltyPayout {
+                recipient: Addr::unchecked("artist"),
+                amount: coin(70, NATIVE_DENOM),
+            }]
+        );
+
+        // a cw2981 royalty above the max royalty is capped at 10% of the price
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { msg, .. }
+                if from_binary::<Cw721QueryMsg<Cw2981QueryMsg>>(msg).is_ok() =>
+            {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&RoyaltiesInfoResponse {
+                        address: "artist".to_string(),
+                        royalty_amount: Uint128::from(500u128),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "cw2981".to_string(),
+            }),
+        });
+        let royalties = collection_royalties(deps.as_ref(), &collection, 1, &price).unwrap();
+        assert_eq!(
+            royalties,
+            vec![RoyaltyPayout {
+                recipient: Addr::unchecked("artist"),
+                amount: coin(100, NATIVE_DENOM),
+            }]
+        );
+
+        // plain cw721 collections support neither, so no royalties are paid
+        deps.querier
+            .update_wasm(|_| SystemResult::Ok(ContractResult::Err("unknown variant".to_string())));
+        let royalties = collection_royalties(deps.as_ref(), &collection, 1, &price).unwrap();
+        assert!(royalties.is_empty());
+
+        let res = crate::query::query_simulate_payout(
+            deps.as_ref(),
+            collection.clone(),
+            1,
+            price.clone(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.payout.seller_amount, coin(980, NATIVE_DENOM));
+
+        // the finders fee is capped like it is for bids
+        let err = crate::query::query_simulate_payout(
+            deps.as_ref(),
+            collection,
+            1,
+            price,
+            Some(Addr::unchecked("finder")),
+            Some(1001),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err(ContractError::InvalidFindersFeeBps(1001).to_string())
+        );
+    }
+
+    #[test]
+    fn try_update_params() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let before = SUDO_PARAMS.load(deps.as_ref().storage).unwrap();
+
+        let update_msg = SudoMsg::UpdateParams {
+            trading_fee_bps: Some(300),
+            ask_expiry: None,
+            bid_expiry: None,
+            operators: None,
+            max_finders_fee_bps: None,
+            min_price: None,
+            stale_bid_duration: None,
+            bid_removal_reward_bps: None,
+            listing_fee: None,
+            auction_extension_window: None,
+            auction_extension_duration: None,
+            min_bid_increment_bps: None,
+            listing_fee_destination: None,
+            listing_fee_grace_period: None,
+            max_royalty_bps: None,
+        };
+        crate::sudo::sudo(deps.as_mut(), mock_env(), update_msg).unwrap();
+
+        // only the trading fee changes
+        let after = SUDO_PARAMS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(after.trading_fee_percent, Decimal::percent(3));
+        assert_eq!(
+            after,
+            SudoParams {
+                trading_fee_percent: Decimal::percent(3),
+                ..before
+            }
+        );
+
+        // stale bids can only be timed in seconds
+        let update_msg = SudoMsg::UpdateParams {
+            trading_fee_bps: None,
+            ask_expiry: None,
+            bid_expiry: None,
+            operators: None,
+            max_finders_fee_bps: None,
+            min_price: None,
+            stale_bid_duration: Some(Duration::Height(100)),
+            bid_removal_reward_bps: None,
+            listing_fee: None,
+            auction_extension_window: None,
+            auction_extension_duration: None,
+            min_bid_increment_bps: None,
+            listing_fee_destination: None,
+            listing_fee_grace_period: None,
+            max_royalty_bps: None,
+        };
+        let err = crate::sudo::sudo(deps.as_mut(), mock_env(), update_msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidStaleBidDuration {});
+
+        // auction extensions are bounded
+        let update_msg = SudoMsg::UpdateParams {
+            trading_fee_bps: None,
+            ask_expiry: None,
+            bid_expiry: None,
+            operators: None,
+            max_finders_fee_bps: None,
+            min_price: None,
+            stale_bid_duration: None,
+            bid_removal_reward_bps: None,
+            listing_fee: None,
+            auction_extension_window: Some(MAX_AUCTION_EXTENSION + 1),
+            auction_extension_duration: None,
+            min_bid_increment_bps: None,
+            listing_fee_destination: None,
+            listing_fee_grace_period: None,
+            max_royalty_bps: None,
+        };
+        let err = crate::sudo::sudo(deps.as_mut(), mock_env(), update_msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidAuctionExtension(MAX_AUCTION_EXTENSION + 1)
+        );
+    }
+
+    #[test]
+    fn try_ask_queries() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        for token_id in 1..=3 {
+            let mut ask = setup_ask(deps.as_mut(), "seller", token_id);
+            ask.price = Uint128::from(400u128 - token_id as u128 * 100);
+            ask.is_active = token_id != 2;
+            store_ask(deps.as_mut().storage, &ask).unwrap();
+        }
+
+        let res = query_asks(deps.as_ref(), collection.clone(), None, None, None).unwrap();
+        let token_ids: Vec<TokenId> = res.asks.iter().map(|ask| ask.token_id).collect();
+        assert_eq!(token_ids, vec![1, 3]);
+
+        let res = query_asks(
+            deps.as_ref(),
+            collection.clone(),
+            Some(true),
+            Some(1),
+            Some(1),
+        )
+        .unwrap();
+        assert_eq!(res.asks.len(), 1);
+        assert_eq!(res.asks[0].token_id, 2);
+
+        // token 3 is the cheapest
+        let res = query_asks_sorted_by_price(
+            deps.as_ref(),
+            collection.clone(),
+            Some(true),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let token_ids: Vec<TokenId> = res.asks.iter().map(|ask| ask.token_id).collect();
+        assert_eq!(token_ids, vec![3, 2, 1]);
+
+        let res = query_asks_sorted_by_price(
+            deps.as_ref(),
+            collection.clone(),
+            Some(true),
+            Some(AskOffset::new(Uint128::from(200u128), 2)),
+            None,
+            Some(true),
+        )
+        .unwrap();
+        let token_ids: Vec<TokenId> = res.asks.iter().map(|ask| ask.token_id).collect();
+        assert_eq!(token_ids, vec![3]);
+
+        let res = query_asks_by_seller(
+            deps.as_ref(),
+            Addr::unchecked("seller"),
+            Some(true),
+            Some(CollectionOffset::new(collection.to_string(), 1)),
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.asks.len(), 2);
+
+        let res = query_ask_count(deps.as_ref(), collection.clone()).unwrap();
+        assert_eq!(res.count, 3);
+
+        // limits are capped at the max query limit
+        for token_id in 4..=110 {
+            setup_ask(deps.as_mut(), "seller", token_id);
+        }
+        let res = query_asks(deps.as_ref(), collection, None, None, Some(500)).unwrap();
+        assert_eq!(res.asks.len(), 100);
+    }
+
+    #[test]
+    fn try_bid_queries() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        let bidder = Addr::unchecked("bidder");
+        let now = mock_env().block.time;
+        for token_id in 1..=3 {
+            let bid = Bid::new(
+                collection.clone(),
+                token_id,
+                bidder.clone(),
+                coin(100 * token_id as u128, NATIVE_DENOM),
+                None,
+                now.plus_seconds(1000 - token_id as u64 * 100),
+            );
+            store_bid(deps.as_mut().storage, &bid).unwrap();
+        }
+        let other_bid = Bid::new(
+            collection.clone(),
+            1,
+            Addr::unchecked("other"),
+            coin(150, NATIVE_DENOM),
+            None,
+            now.plus_seconds(1000),
+        );
+        store_bid(deps.as_mut().storage, &other_bid).unwrap();
+
+        let res = query_bid(deps.as_ref(), collection.clone(), 1, bidder.clone()).unwrap();
+        assert_eq!(res.bid.unwrap().price, Uint128::from(100u128));
+
+        let res = query_bids(deps.as_ref(), collection.clone(), 1, None, None).unwrap();
+        assert_eq!(res.bids.len(), 2);
+        let res = query_bids(
+            deps.as_ref(),
+            collection.clone(),
+            1,
+            Some(bidder.to_string()),
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.bids, vec![other_bid]);
+
+        let res = query_bids_by_bidder(deps.as_ref(), bidder.clone(), None, Some(2)).unwrap();
+        let token_ids: Vec<TokenId> = res.bids.iter().map(|bid| bid.token_id).collect();
+        assert_eq!(token_ids, vec![1, 2]);
+
+        // token 3 expires first
+        let res =
+            query_bids_by_bidder_sorted_by_expiration(deps.as_ref(), bidder.clone(), None, None)
+                .unwrap();
+        let token_ids: Vec<TokenId> = res.bids.iter().map(|bid| bid.token_id).collect();
+        assert_eq!(token_ids, vec![3, 2, 1]);
+        let res = query_bids_by_bidder_sorted_by_expiration(
+            deps.as_ref(),
+            bidder.clone(),
+            Some(BidExpirationOffset::new(
+                now.plus_seconds(700),
+                collection.to_string(),
+                3,
+            )),
+            None,
+        )
+        .unwrap();
+        let token_ids: Vec<TokenId> = res.bids.iter().map(|bid| bid.token_id).collect();
+        assert_eq!(token_ids, vec![2, 1]);
+
+        let res = query_bids_sorted_by_price(
+            deps.as_ref(),
+            collection.clone(),
+            Some(BidOffset::new(
+                Uint128::from(150u128),
+                1,
+                Addr::unchecked("other"),
+            )),
+            None,
+            Some(true),
+        )
+        .unwrap();
+        let prices: Vec<Uint128> = res.bids.iter().map(|bid| bid.price).collect();
+        assert_eq!(prices, vec![Uint128::from(100u128)]);
+
+        // paging keeps working after the offset bid is removed
+        bids()
+            .remove(deps.as_mut().storage, bid_key(&collection, 3, &bidder))
+            .unwrap();
+        let res = query_bids_by_bidder_sorted_by_expiration(
+            deps.as_ref(),
+            bidder,
+            Some(BidExpirationOffset::new(
+                now.plus_seconds(700),
+                collection.to_string(),
+                3,
+            )),
+            None,
+        )
+        .unwrap();
+        let token_ids: Vec<TokenId> = res.bids.iter().map(|bid| bid.token_id).collect();
+        assert_eq!(token_ids, vec![2, 1]);
+    }
+
+    #[test]
+    fn try_remove_bid() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        let bidder = Addr::unchecked("bidder");
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+
+        let err = execute_remove_bid(
+            deps.as_mut(),
+            mock_info("bidder", &[]),
+            collection.clone(),
+            1,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::BidNotFound {});
+
+        let bid = Bid::new(
+            collection.clone(),
+            1,
+            bidder.clone(),
+            coin(100, NATIVE_DENOM),
+            None,
+            mock_env().block.time.plus_seconds(100),
+        );
+        store_bid(deps.as_mut().storage, &bid).unwrap();
+
+        let res = execute_remove_bid(
+            deps.as_mut(),
+            mock_info("bidder", &[]),
+            collection.clone(),
+            1,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: bidder.to_string(),
+                amount: vec![coin(100, NATIVE_DENOM)],
+            }
+            .into()
+        );
+        assert_eq!(res.events[0].ty, "remove-bid");
+        assert!(!bids().has(deps.as_ref().storage, bid_key(&collection, 1, &bidder)));
+    }
+
+    #[test]
+    fn try_update_ask_state() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        setup_ask(deps.as_mut(), "seller", 1);
+
+        let err = execute_update_ask_state(
+            deps.as_mut(),
+            mock_info("random", &[]),
+            collection.clone(),
+            1,
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::UnauthorizedOperator {});
+
+        execute_update_ask_state(
+            deps.as_mut(),
+            mock_info("operator", &[]),
+            collection.clone(),
+            1,
+            false,
+        )
+        .unwrap();
+        let ask = asks()
+            .load(deps.as_ref().storage, ask_key(&collection, 1))
+            .unwrap();
+        assert!(!ask.is_active);
+
+        let err = execute_update_ask_state(
+            deps.as_mut(),
+            mock_info("operator", &[]),
+            collection,
+            1,
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AskUnchanged {});
+    }
+
+    #[test]
+    fn try_sync_ask() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        setup_ask(deps.as_mut(), "seller", 1);
+
+        // the token was transferred away, so anyone can deactivate the ask
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { msg, .. } => match from_binary(msg).unwrap() {
+                Cw721QueryMsg::<Empty>::OwnerOf { .. } => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&OwnerOfResponse {
+                        owner: "buyer".to_string(),
+                        approvals: vec![],
+                    })
+                    .unwrap(),
+                )),
+                _ => SystemResult::Ok(ContractResult::Err("not approved".to_string())),
+            },
+            _ => panic!("unexpected query"),
+        });
+        let res = execute_sync_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            collection.clone(),
+            1,
+        )
+        .unwrap();
+        assert_eq!(res.events[0].ty, "sync-ask");
+        let ask = asks()
+            .load(deps.as_ref().storage, ask_key(&collection, 1))
+            .unwrap();
+        assert!(!ask.is_active);
+
+        // the seller owns the token again and approved the marketplace
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { msg, .. } => match from_binary(msg).unwrap() {
+                Cw721QueryMsg::<Empty>::OwnerOf { .. } => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&OwnerOfResponse {
+                        owner: "seller".to_string(),
+                        approvals: vec![],
+                    })
+                    .unwrap(),
+                )),
+                _ => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&cw721::ApprovalResponse {
+                        approval: cw721::Approval {
+                            spender: MOCK_CONTRACT_ADDR.to_string(),
+                            expires: Expiration::Never {},
+                        },
+                    })
+                    .unwrap(),
+                )),
+            },
+            _ => panic!("unexpected query"),
+        });
+        execute_sync_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            collection.clone(),
+            1,
+        )
+        .unwrap();
+        let ask = asks()
+            .load(deps.as_ref().storage, ask_key(&collection, 1))
+            .unwrap();
+        assert!(ask.is_active);
+
+        let err = execute_sync_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            collection,
+            1,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AskUnchanged {});
+    }
+
+    #[test]
+    fn try_redeem_voucher() {
+        let mut deps = signing_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let key = signing_key(1);
+        let creator_pubkey = signer_pubkey(&key);
+        let creator = pubkey_to_addr(deps.as_ref().api, &creator_pubkey).unwrap();
+        let voucher = MintVoucher {
+            token_uri: Some("ipfs://voucher".to_string()),
+            price: coin(100, NATIVE_DENOM),
+            creator_pubkey,
+            nonce: 7,
+            expires: mock_env().block.time.plus_seconds(60),
+        };
+        let signature = sign(&key, &voucher);
+
+        // A signature over a different voucher is rejected
+        let mut tampered = voucher.clone();
+        tampered.nonce = 8;
+        let err = execute_redeem_voucher(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[coin(100, NATIVE_DENOM)]),
+            tampered,
+            signature.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSignature {});
+
+        let res = execute_redeem_voucher(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[coin(100, NATIVE_DENOM)]),
+            voucher.clone(),
+            signature.clone(),
+        )
+        .unwrap();
+
+        // The token is minted to the buyer
+        let token = crate::query::query_token(deps.as_ref(), 1).unwrap();
+        assert_eq!(token.owner, Addr::unchecked("buyer"));
+        assert_eq!(token.token_uri, Some("ipfs://voucher".to_string()));
+
+        // The creator is paid the price minus the 2% trading fee
+        let payouts: Vec<_> = res
+            .messages
+            .iter()
+            .filter_map(|sub| match &sub.msg {
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                    Some((to_address.clone(), amount.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            payouts,
+            vec![(creator.to_string(), vec![coin(98, NATIVE_DENOM)])]
+        );
+
+        // The same nonce can't be redeemed twice
+        let err = execute_redeem_voucher(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[coin(100, NATIVE_DENOM)]),
+            voucher,
+            signature,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::VoucherAlreadyRedeemed {});
+    }
+
+    fn bank_sends(res: &Response) -> Vec<(String, Vec<Coin>)> {
+        res.messages
+            .iter()
+            .filter_map(|sub| match &sub.msg {
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                    Some((to_address.clone(), amount.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn try_fill_signed_ask() {
+        let mut deps = signing_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let key = signing_key(2);
+        let seller_pubkey = signer_pubkey(&key);
+        let seller = pubkey_to_addr(deps.as_ref().api, &seller_pubkey).unwrap();
+        let token_id = mint_token(deps.as_mut().storage, seller.clone(), None, vec![]).unwrap();
+        let order = SignedAsk {
+            collection: MOCK_CONTRACT_ADDR.to_string(),
+            token_id: token_id as TokenId,
+            seller: seller.to_string(),
+            price: coin(100, NATIVE_DENOM),
+            funds_recipient: None,
+            reserve_for: None,
+            finders_fee_bps: Some(100),
+            expires_at: mock_env().block.time.plus_seconds(60),
+            nonce: 1,
+            seller_pubkey,
+        };
+        let signature = sign(&key, &order);
+
+        // The buyer can't be their own finder
+        let err = execute_fill_signed_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[coin(100, NATIVE_DENOM)]),
+            order.clone(),
+            signature.clone(),
+            Some(Addr::unchecked("buyer")),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFinder(_)));
+
+        // A signature over a different order is rejected
+        let mut tampered = order.clone();
+        tampered.price = coin(10, NATIVE_DENOM);
+        let err = execute_fill_signed_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[coin(10, NATIVE_DENOM)]),
+            tampered,
+            signature.clone(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSignature {});
+
+        // An order priced in a cw20 token can't be paid for
+        crate::sudo::sudo_add_payment_asset(
+            deps.as_mut(),
+            "cw20token".to_string(),
+            AssetType::Cw20,
+        )
+        .unwrap();
+        let mut cw20_order = order.clone();
+        cw20_order.price = coin(100, "cw20token");
+        cw20_order.nonce = 2;
+        let cw20_signature = sign(&key, &cw20_order);
+        let err = execute_fill_signed_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[]),
+            cw20_order,
+            cw20_signature,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NativeDenomRequired("cw20token".to_string())
+        );
+
+        let res = execute_fill_signed_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[coin(100, NATIVE_DENOM)]),
+            order.clone(),
+            signature.clone(),
+            Some(Addr::unchecked("finder")),
+        )
+        .unwrap();
+        assert_eq!(
+            crate::query::query_token(deps.as_ref(), token_id)
+                .unwrap()
+                .owner,
+            Addr::unchecked("buyer")
+        );
+        // 2% trading fee is burned, 1% goes to the finder and the rest to the seller
+        assert_eq!(
+            bank_sends(&res),
+            vec![
+                ("finder".to_string(), vec![coin(1, NATIVE_DENOM)]),
+                (seller.to_string(), vec![coin(97, NATIVE_DENOM)]),
+            ]
+        );
+
+        // A filled order can't be replayed
+        let err = execute_fill_signed_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[coin(100, NATIVE_DENOM)]),
+            order,
+            signature,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::OrderCancelledOrFilled {});
+    }
+
+    #[test]
+    fn try_cancel_signed_orders() {
+        let mut deps = signing_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let key = signing_key(3);
+        let seller_pubkey = signer_pubkey(&key);
+        let seller = pubkey_to_addr(deps.as_ref().api, &seller_pubkey).unwrap();
+        let token_id = mint_token(deps.as_mut().storage, seller.clone(), None, vec![]).unwrap();
+        let order = SignedAsk {
+            collection: MOCK_CONTRACT_ADDR.to_string(),
+            token_id: token_id as TokenId,
+            seller: seller.to_string(),
+            price: coin(100, NATIVE_DENOM),
+            funds_recipient: None,
+            reserve_for: None,
+            finders_fee_bps: None,
+            expires_at: mock_env().block.time.plus_seconds(60),
+            nonce: 5,
+            seller_pubkey,
+        };
+        let signature = sign(&key, &order);
+
+        let err = execute_cancel_signed_orders(
+            deps.as_mut(),
+            mock_info(seller.as_str(), &[coin(1, NATIVE_DENOM)]),
+            vec![5],
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Payment(_)));
+
+        // Cancelling someone else's nonce leaves the seller's order untouched
+        execute_cancel_signed_orders(deps.as_mut(), mock_info("other", &[]), vec![5]).unwrap();
+        assert!(!SIGNED_ORDER_NONCES.has(deps.as_ref().storage, (&seller, 5)));
+
+        execute_cancel_signed_orders(deps.as_mut(), mock_info(seller.as_str(), &[]), vec![4, 5])
+            .unwrap();
+        let err = execute_fill_signed_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[coin(100, NATIVE_DENOM)]),
+            order,
+            signature,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::OrderCancelledOrFilled {});
+        assert_eq!(
+            crate::query::query_token(deps.as_ref(), token_id)
+                .unwrap()
+                .owner,
+            seller
+        );
+    }
+
+    /// Lists a natively minted token in an auction, so settling can move it
+    fn setup_native_auction(deps: DepsMut, seller: &str) -> Ask {
+        let token_id = mint_token(deps.storage, Addr::unchecked(seller), None, vec![]).unwrap();
+        let ask = Ask {
+            sale_type: SaleType::Auction,
+            collection: Addr::unchecked(MOCK_CONTRACT_ADDR),
+            token_id: token_id as TokenId,
+            seller: Addr::unchecked(seller),
+            price: Uint128::from(100u128),
+            denom: NATIVE_DENOM.to_string(),
+            funds_recipient: None,
+            reserve_for: None,
+            finders_fee_bps: None,
+            expires_at: mock_env().block.time.plus_seconds(2 * 24 * 60 * 60),
+            is_active: true,
+            dutch_auction: None,
+            min_bid_increment_percent: None,
+        };
+        store_ask(deps.storage, &ask).unwrap();
+        ask
+    }
+
+    fn auction_bid_info(ask: &Ask, expires: Timestamp) -> BidInfo {
+        BidInfo {
+            collection: ask.collection.clone(),
+            token_id: ask.token_id,
+            expires,
+            finder: None,
+            finders_fee_bps: None,
+        }
+    }
+
+    #[test]
+    fn try_auction_winning_bid() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let ask = setup_native_auction(deps.as_mut(), "seller");
+        let collection = ask.collection.clone();
+        let bid_expires = ask.expires_at.plus_seconds(24 * 60 * 60);
+
+        execute_set_bid(
+            deps.as_mut(),
+            mock_env(),
+            Addr::unchecked("bidder1"),
+            coin(100, NATIVE_DENOM),
+            SaleType::Auction,
+            auction_bid_info(&ask, bid_expires),
+            false,
+        )
+        .unwrap();
+
+        // the outbid bidder is refunded in full
+        let res = execute_set_bid(
+            deps.as_mut(),
+            mock_env(),
+            Addr::unchecked("bidder2"),
+            coin(150, NATIVE_DENOM),
+            SaleType::Auction,
+            auction_bid_info(&ask, bid_expires),
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            bank_sends(&res),
+            vec![("bidder1".to_string(), vec![coin(100, NATIVE_DENOM)])]
+        );
+        assert!(!bids().has(
+            deps.as_ref().storage,
+            bid_key(&collection, ask.token_id, &Addr::unchecked("bidder1"))
+        ));
+
+        // the seller can't change or close the auction while it has a bid
+        let err = execute_update_ask_price(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("seller", &[]),
+            collection.clone(),
+            ask.token_id,
+            coin(200, NATIVE_DENOM),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AuctionLocked {});
+        let err = execute_remove_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("seller", &[]),
+            collection.clone(),
+            ask.token_id,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AuctionLocked {});
+        let err = execute_set_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("seller", &[]),
+            NFTinfo {
+                sale_type: SaleType::FixedPrice,
+                collection: collection.clone(),
+                token_id: ask.token_id,
+                price: coin(10, NATIVE_DENOM),
+                funds_recipient: None,
+                reserve_for: None,
+                finders_fee_bps: None,
+                expires: ask.expires_at,
+                reserve_price: None,
+                min_bid_increment_bps: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AuctionLocked {});
+        let err = execute_set_dutch_auction(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("seller", &[]),
+            DutchAuctionInfo {
+                collection: collection.clone(),
+                token_id: ask.token_id,
+                start_price: coin(200, NATIVE_DENOM),
+                end_price: coin(10, NATIVE_DENOM),
+                start_time: mock_env().block.time,
+                end_time: ask.expires_at,
+                funds_recipient: None,
+                finders_fee_bps: None,
+                expires: ask.expires_at,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AuctionLocked {});
+
+        let mut env = mock_env();
+        env.block.time = ask.expires_at;
+        let res = execute_settle_auction(
+            deps.as_mut(),
+            env,
+            mock_info("anyone", &[]),
+            collection.clone(),
+            ask.token_id,
+        )
+        .unwrap();
+        let event = res
+            .events
+            .iter()
+            .find(|e| e.ty == "settle-auction")
+            .unwrap();
+        assert_eq!(event.attributes[3].value, "sold");
+        assert_eq!(
+            crate::query::query_token(deps.as_ref(), ask.token_id as u64)
+                .unwrap()
+                .owner,
+            Addr::unchecked("bidder2")
+        );
+        // 2% trading fee is burned and the rest goes to the seller
+        assert_eq!(
+            bank_sends(&res),
+            vec![("seller".to_string(), vec![coin(147, NATIVE_DENOM)])]
+        );
+        assert!(!bids().has(
+            deps.as_ref().storage,
+            bid_key(&collection, ask.token_id, &Addr::unchecked("bidder2"))
+        ));
+    }
+
+    #[test]
+    fn try_auction_bid_expiry() {
+        fn bid(
+            deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+            env: &Env,
+            bidder: &str,
+            ask: &Ask,
+            price: u128,
+            expires: Timestamp,
+        ) -> Result<Response, ContractError> {
+            execute_set_bid(
+                deps.as_mut(),
+                env.clone(),
+                Addr::unchecked(bidder),
+                coin(price, NATIVE_DENOM),
+                SaleType::Auction,
+                auction_bid_info(ask, expires),
+                false,
+            )
+        }
+
+        // failed executions roll back on chain, so every case starts from a fresh auction
+        let setup = || {
+            let mut deps = mock_dependencies();
+            instantiate(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("creator", &[]),
+                instantiate_msg(),
+            )
+            .unwrap();
+            let ask = setup_native_auction(deps.as_mut(), "seller");
+            (deps, ask)
+        };
+
+        // a bid has to stay valid until the auction ends
+        let (mut deps, ask) = setup();
+        let err = bid(
+            &mut deps,
+            &mock_env(),
+            "bidder1",
+            &ask,
+            150,
+            ask.expires_at.minus_seconds(1),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::BidExpiresBeforeAuctionEnd {});
+
+        // a late bid extends the auction past the first bid's expiry
+        let (mut deps, ask) = setup();
+        bid(&mut deps, &mock_env(), "bidder1", &ask, 150, ask.expires_at).unwrap();
+        let mut late = mock_env();
+        late.block.time = ask.expires_at.minus_seconds(60);
+        let bid_expires = late.block.time.plus_seconds(24 * 60 * 60 + 1);
+        bid(&mut deps, &late, "bidder2", &ask, 200, bid_expires).unwrap();
+
+        // settling after the winning bid expired still sells to it
+        let mut env = mock_env();
+        env.block.time = bid_expires.plus_seconds(24 * 60 * 60);
+        let res = execute_settle_auction(
+            deps.as_mut(),
+            env,
+            mock_info("anyone", &[]),
+            ask.collection.clone(),
+            ask.token_id,
+        )
+        .unwrap();
+        let event = res
+            .events
+            .iter()
+            .find(|e| e.ty == "settle-auction")
+            .unwrap();
+        assert_eq!(event.attributes[3].value, "sold");
+        assert_eq!(
+            crate::query::query_token(deps.as_ref(), ask.token_id as u64)
+                .unwrap()
+                .owner,
+            Addr::unchecked("bidder2")
+        );
+    }
+
+    #[test]
+    fn try_accept_bid_during_auction() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let ask = setup_native_auction(deps.as_mut(), "seller");
+        let bid_expires = ask.expires_at.plus_seconds(24 * 60 * 60);
+        execute_set_bid(
+            deps.as_mut(),
+            mock_env(),
+            Addr::unchecked("bidder"),
+            coin(150, NATIVE_DENOM),
+            SaleType::Auction,
+            auction_bid_info(&ask, bid_expires),
+            false,
+        )
+        .unwrap();
+
+        let err = execute_accept_bid(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("seller", &[]),
+            ask.collection.clone(),
+            ask.token_id,
+            Addr::unchecked("bidder"),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AuctionNotEnded {});
+        assert!(asks().has(
+            deps.as_ref().storage,
+            ask_key(&ask.collection, ask.token_id)
+        ));
+        assert_eq!(
+            crate::query::query_token(deps.as_ref(), ask.token_id as u64)
+                .unwrap()
+                .owner,
+            Addr::unchecked("seller")
+        );
+    }
+
+    #[test]
+    fn try_dutch_auction_buy() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+
+        let start_time = mock_env().block.time;
+        let mut ask = setup_native_auction(deps.as_mut(), "seller");
+        ask.sale_type = SaleType::DutchAuction;
+        ask.price = Uint128::from(1000u128);
+        ask.dutch_auction = Some(DutchAuction {
+            start_price: Uint128::from(1000u128),
+            end_price: Uint128::from(200u128),
+            start_time,
+            end_time: start_time.plus_seconds(1000),
+        });
+        store_ask(deps.as_mut().storage, &ask).unwrap();
+        let bid_expires = start_time.plus_seconds(2 * 24 * 60 * 60);
+
+        // halfway through the price has decayed to 600
+        let mut env = mock_env();
+        env.block.time = start_time.plus_seconds(500);
+        let err = execute_set_bid(
+            deps.as_mut(),
+            env.clone(),
+            Addr::unchecked("buyer"),
+            coin(599, NATIVE_DENOM),
+            SaleType::DutchAuction,
+            auction_bid_info(&ask, bid_expires),
+            true,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidPrice {});
+
+        // paying more than the current price buys at the current price and refunds the rest
+        let res = execute_set_bid(
+            deps.as_mut(),
+            env,
+            Addr::unchecked("buyer"),
+            coin(700, NATIVE_DENOM),
+            SaleType::DutchAuction,
+            auction_bid_info(&ask, bid_expires),
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            crate::query::query_token(deps.as_ref(), ask.token_id as u64)
+                .unwrap()
+                .owner,
+            Addr::unchecked("buyer")
+        );
+        // 2% trading fee on 600 is burned and the rest goes to the seller
+        assert_eq!(
+            bank_sends(&res),
+            vec![
+                ("buyer".to_string(), vec![coin(100, NATIVE_DENOM)]),
+                ("seller".to_string(), vec![coin(588, NATIVE_DENOM)]),
+            ]
+        );
+        assert!(!asks().has(
+            deps.as_ref().storage,
+            ask_key(&ask.collection, ask.token_id)
+        ));
+        assert!(!bids().has(
+            deps.as_ref().storage,
+            bid_key(&ask.collection, ask.token_id, &Addr::unchecked("buyer"))
+        ));
+    }
+
+    #[test]
+    fn try_auction_reserve_price() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let ask = setup_native_auction(deps.as_mut(), "seller");
+        let key = ask_key(&ask.collection, ask.token_id);
+        AUCTION_RESERVE_PRICES
+            .save(deps.as_mut().storage, key.clone(), &Uint128::from(200u128))
+            .unwrap();
+        let bid_expires = ask.expires_at.plus_seconds(24 * 60 * 60);
+
+        // the reserve stays out of the ask returned by queries
+        let res =
+            crate::query::query_ask(deps.as_ref(), ask.collection.clone(), ask.token_id).unwrap();
+        assert_eq!(res.ask.unwrap().price, Uint128::from(100u128));
+
+        let err = execute_set_bid(
+            deps.as_mut(),
+            mock_env(),
+            Addr::unchecked("bidder"),
+            coin(150, NATIVE_DENOM),
+            SaleType::Auction,
+            auction_bid_info(&ask, bid_expires),
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ReservePriceNotMet {});
+
+        execute_set_bid(
+            deps.as_mut(),
+            mock_env(),
+            Addr::unchecked("bidder"),
+            coin(200, NATIVE_DENOM),
+            SaleType::Auction,
+            auction_bid_info(&ask, bid_expires),
+            false,
+        )
+        .unwrap();
+
+        let mut env = mock_env();
+        env.block.time = ask.expires_at;
+        let res = execute_settle_auction(
+            deps.as_mut(),
+            env,
+            mock_info("anyone", &[]),
+            ask.collection.clone(),
+            ask.token_id,
+        )
+        .unwrap();
+        let event = res
+            .events
+            .iter()
+            .find(|e| e.ty == "settle-auction")
+            .unwrap();
+        assert_eq!(event.attributes[3].value, "sold");
+        assert_eq!(
+            crate::query::query_token(deps.as_ref(), ask.token_id as u64)
+                .unwrap()
+                .owner,
+            Addr::unchecked("bidder")
+        );
+        assert!(!asks().has(deps.as_ref().storage, key.clone()));
+        assert!(!AUCTION_RESERVE_PRICES.has(deps.as_ref().storage, key));
+    }
+
+    #[test]
+    fn try_remove_ask_clears_reserve_price() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let ask = setup_native_auction(deps.as_mut(), "seller");
+        let key = ask_key(&ask.collection, ask.token_id);
+        AUCTION_RESERVE_PRICES
+            .save(deps.as_mut().storage, key.clone(), &Uint128::from(200u128))
+            .unwrap();
+
+        let mut env = mock_env();
+        env.block.time = ask.expires_at;
+        execute_remove_stale_ask(
+            deps.as_mut(),
+            env,
+            mock_info("operator", &[]),
+            ask.collection.clone(),
+            ask.token_id,
+        )
+        .unwrap();
+        assert!(!AUCTION_RESERVE_PRICES.has(deps.as_ref().storage, key));
+    }
+
+    #[test]
+    fn try_mint_and_list_listing_fee() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let mut params = SUDO_PARAMS.load(deps.as_ref().storage).unwrap();
+        params.listing_fee = Uint128::from(10u128);
+        params.listing_fee_grace_period = 100;
+        SUDO_PARAMS.save(deps.as_mut().storage, &params).unwrap();
+        let msg = ExecuteMsg::MintAndList {
+            token_uri: None,
+            base_price: vec![coin(100, NATIVE_DENOM)],
+            sale_type: SaleType::FixedPrice,
+            price: None,
+            funds_recipient: None,
+            reserve_for: None,
+            finders_fee_bps: None,
+            expires: mock_env().block.time.plus_seconds(2 * 24 * 60 * 60),
+        };
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minter", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidListingFee(Uint128::zero()));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minter", &[coin(10, NATIVE_DENOM)]),
+            msg,
+        )
+        .unwrap();
+        let key = ask_key(&mock_env().contract.address, 1);
+        assert!(asks().has(&deps.storage, key.clone()));
+        assert_eq!(
+            LISTING_FEES.load(&deps.storage, key).unwrap().fee,
+            coin(10, NATIVE_DENOM)
+        );
+    }
+
+    #[test]
+    fn try_fee_msg() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        crate::sudo::sudo_add_payment_asset(
+            deps.as_mut(),
+            "cw20token".to_string(),
+            AssetType::Cw20,
+        )
+        .unwrap();
+
+        let msg = fee_msg(
+            deps.as_ref().storage,
+            &FeeDestination::CommunityPool,
+            coin(10, NATIVE_DENOM),
+        )
+        .unwrap();
+        assert_eq!(
+            msg,
+            create_fund_community_pool_msg(vec![coin(10, NATIVE_DENOM)])
+        );
+
+        // cw20 tokens can't fund the community pool
+        fee_msg(
+            deps.as_ref().storage,
+            &FeeDestination::CommunityPool,
+            coin(10, "cw20token"),
+        )
+        .unwrap_err();
+
+        // but they can go to a fee collector
+        let msg = fee_msg(
+            deps.as_ref().storage,
+            &FeeDestination::FeeCollector {
+                address: Addr::unchecked("collector"),
+            },
+            coin(10, "cw20token"),
+        )
+        .unwrap();
+        assert!(
+            matches!(msg, CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. })
+            if contract_addr == "cw20token")
+        );
+    }
+
+    #[test]
+    fn try_ask_expiry_range() {
+        let mut deps = mock_dependencies();
+        let collection = Addr::unchecked("collection");
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        // asks must live at least a day
+        let expires = mock_env().block.time.plus_seconds(60);
+
+        let err = execute_set_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("seller", &[]),
+            NFTinfo {
+                sale_type: SaleType::FixedPrice,
+                collection: collection.clone(),
+                token_id: 1,
+                price: coin(100, NATIVE_DENOM),
+                funds_recipient: None,
+                reserve_for: None,
+                finders_fee_bps: None,
+                expires,
+                reserve_price: None,
+                min_bid_increment_bps: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ExpiryRange(crate::helpers::ExpiryRangeError::InvalidExpirationRange {})
+        );
+
+        let err = execute_set_dutch_auction(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("seller", &[]),
+            DutchAuctionInfo {
+                collection,
+                token_id: 1,
+                start_price: coin(1000, NATIVE_DENOM),
+                end_price: coin(200, NATIVE_DENOM),
+                start_time: mock_env().block.time,
+                end_time: expires,
+                funds_recipient: None,
+                finders_fee_bps: None,
+                expires,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ExpiryRange(crate::helpers::ExpiryRangeError::InvalidExpirationRange {})
+        );
+    }
+
+    #[test]
+    fn try_auction_rebid() {
+        fn rebid(
+            deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+            ask: &Ask,
+            price: u128,
+        ) -> Result<Response, ContractError> {
+            execute_set_bid(
+                deps.as_mut(),
+                mock_env(),
+                Addr::unchecked("bidder"),
+                coin(price, NATIVE_DENOM),
+                SaleType::Auction,
+                auction_bid_info(ask, ask.expires_at.plus_seconds(24 * 60 * 60)),
+                false,
+            )
+        }
+
+        // failed executions roll back on chain, so every case starts from a fresh auction
+        let setup = || {
+            let mut deps = mock_dependencies();
+            instantiate(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("creator", &[]),
+                instantiate_msg(),
+            )
+            .unwrap();
+            let ask = setup_native_auction(deps.as_mut(), "seller");
+            rebid(&mut deps, &ask, 200).unwrap();
+            (deps, ask)
+        };
+
+        // the top bidder can't lower their bid, nor raise it by less than the increment
+        let (mut deps, ask) = setup();
+        assert_eq!(
+            rebid(&mut deps, &ask, 100).unwrap_err(),
+            ContractError::BidTooLow {}
+        );
+        let (mut deps, ask) = setup();
+        assert_eq!(
+            rebid(&mut deps, &ask, 205).unwrap_err(),
+            ContractError::BidIncrementTooSmall(Uint128::from(210u128))
+        );
+
+        // raising the bid refunds the old one once
+        let (mut deps, ask) = setup();
+        let res = rebid(&mut deps, &ask, 210).unwrap();
+        assert_eq!(
+            bank_sends(&res),
+            vec![("bidder".to_string(), vec![coin(200, NATIVE_DENOM)])]
+        );
+        let stored = bids()
+            .load(
+                deps.as_ref().storage,
+                bid_key(&ask.collection, ask.token_id, &Addr::unchecked("bidder")),
+            )
+            .unwrap();
+        assert_eq!(stored.price, Uint128::from(210u128));
+    }
+
+    #[test]
+    fn try_remove_stale_auction_with_bid() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let ask = setup_native_auction(deps.as_mut(), "seller");
+        execute_set_bid(
+            deps.as_mut(),
+            mock_env(),
+            Addr::unchecked("bidder"),
+            coin(150, NATIVE_DENOM),
+            SaleType::Auction,
+            auction_bid_info(&ask, ask.expires_at.plus_seconds(24 * 60 * 60)),
+            false,
+        )
+        .unwrap();
+
+        // an ended auction with a bid has to be settled, not pruned
+        let mut env = mock_env();
+        env.block.time = ask.expires_at;
+        let err = execute_remove_stale_ask(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("operator", &[]),
+            ask.collection.clone(),
+            ask.token_id,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AuctionLocked {});
+        let res = execute_remove_stale_asks(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("operator", &[]),
+            ask.collection.clone(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.events[0].attributes[3].value, "0");
+        assert!(asks().has(
+            deps.as_ref().storage,
+            ask_key(&ask.collection, ask.token_id)
+        ));
+
+        let res = execute_settle_auction(
+            deps.as_mut(),
+            env,
+            mock_info("anyone", &[]),
+            ask.collection.clone(),
+            ask.token_id,
+        )
+        .unwrap();
+        let event = res
+            .events
+            .iter()
+            .find(|e| e.ty == "settle-auction")
+            .unwrap();
+        assert_eq!(event.attributes[3].value, "sold");
+    }
+
+    #[test]
+    fn try_sell_around_running_auction() {
+        let mut deps = signing_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+        let key = signing_key(4);
+        let seller_pubkey = signer_pubkey(&key);
+        let seller = pubkey_to_addr(deps.as_ref().api, &seller_pubkey).unwrap();
+        let ask = setup_native_auction(deps.as_mut(), seller.as_str());
+        execute_set_bid(
+            deps.as_mut(),
+            mock_env(),
+            Addr::unchecked("bidder"),
+            coin(150, NATIVE_DENOM),
+            SaleType::Auction,
+            auction_bid_info(&ask, ask.expires_at.plus_seconds(24 * 60 * 60)),
+            false,
+        )
+        .unwrap();
+
+        // a collection bid can't take the token out of a running auction
+        execute_set_collection_bid(
+            deps.as_mut(),
+            mock_env(),
+            Addr::unchecked("collector"),
+            coin(500, NATIVE_DENOM),
+            ask.collection.clone(),
+            None,
+            mock_env().block.time.plus_seconds(2 * 24 * 60 * 60),
+        )
+        .unwrap();
+        let err = execute_accept_collection_bid(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(seller.as_str(), &[]),
+            ask.collection.clone(),
+            ask.token_id,
+            Addr::unchecked("collector"),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AuctionNotEnded {});
+
+        // neither can a signed ask
+        let order = SignedAsk {
+            collection: ask.collection.to_string(),
+            token_id: ask.token_id,
+            seller: seller.to_string(),
+            price: coin(500, NATIVE_DENOM),
+            funds_recipient: None,
+            reserve_for: None,
+            finders_fee_bps: None,
+            expires_at: mock_env().block.time.plus_seconds(60),
+            nonce: 1,
+            seller_pubkey,
+        };
+        let signature = sign(&key, &order);
+        let err = execute_fill_signed_ask(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("buyer", &[coin(500, NATIVE_DENOM)]),
+            order,
+            signature,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AuctionNotEnded {});
+
+        assert_eq!(
+            crate::query::query_token(deps.as_ref(), ask.token_id as u64)
+                .unwrap()
+                .owner,
+            seller
+        );
+        assert!(asks().has(
+            deps.as_ref().storage,
+            ask_key(&ask.collection, ask.token_id)
+        ));
+    }
+
+    #[test]
+    fn invalid_initialization() {
+        let mut deps = mock_dependencies();
+        let info = mock_info("creator", &[]);
+
+        let mut msg = instantiate_msg();
+        msg.ask_expiry = ExpiryRange::new(100, 10);
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ExpiryRange(crate::helpers::ExpiryRangeError::InvalidExpiry {})
+        );
+
+        let mut msg = instantiate_msg();
+        msg.trading_fee_bps = 10001;
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidTradingFeeBps(10001));
+
+        let mut msg = instantiate_msg();
+        msg.stale_bid_duration = Duration::Height(100);
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidStaleBidDuration {});
+
+        let mut msg = instantiate_msg();
+        msg.auction_extension_duration = MAX_AUCTION_EXTENSION + 1;
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidAuctionExtension(MAX_AUCTION_EXTENSION + 1)
+        );
+    }
+}
@@ -1,13 +1,27 @@
-use cosmwasm_std::{StdError, Uint128};
+use cosmwasm_std::{StdError, Uint128, VerificationError};
 use cw_utils::PaymentError;
 use sg_controllers::HookError;
 use thiserror::Error;
 
+use crate::helpers::ExpiryRangeError;
+
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("{0}")]
+    Hook(#[from] HookError),
+
+    #[error("{0}")]
+    ExpiryRange(#[from] ExpiryRangeError),
+
+    #[error("{0}")]
+    Verification(#[from] VerificationError),
+
     #[error("Unauthorized")]
     Unauthorized {},
     // Add any other custom errors you like here.
@@ -18,6 +32,12 @@ pub enum ContractError {
     #[error("InvalidPrice")]
     InvalidPrice {},
 
+    #[error("InvalidDenom: {0}")]
+    InvalidDenom(String),
+
+    #[error("Signed orders are paid in native funds, {0} is a cw20 token")]
+    NativeDenomRequired(String),
+
     #[error("InvalidFinder: {0}")]
     InvalidFinder(String),
 
@@ -27,6 +47,21 @@ pub enum ContractError {
     #[error("Invalid finders fee bps: {0}")]
     InvalidFindersFeeBps(u64),
 
+    #[error("Invalid trading fee bps: {0}")]
+    InvalidTradingFeeBps(u64),
+
+    #[error("Invalid bid removal reward bps: {0}")]
+    InvalidBidRemovalRewardBps(u64),
+
+    #[error("Invalid min bid increment bps: {0}")]
+    InvalidBidIncrementBps(u64),
+
+    #[error("Stale bid duration must be set in seconds")]
+    InvalidStaleBidDuration {},
+
+    #[error("Invalid auction extension seconds: {0}")]
+    InvalidAuctionExtension(u64),
+
     #[error("AskExpired")]
     AskExpired {},
 
@@ -36,6 +71,12 @@ pub enum ContractError {
     #[error("AskUnchanged")]
     AskUnchanged {},
 
+    #[error("AskNotFound")]
+    AskNotFound {},
+
+    #[error("AskNotExpired")]
+    AskNotExpired {},
+
     #[error("Token reserved")]
     TokenReserved {},
 
@@ -51,9 +92,90 @@ pub enum ContractError {
     #[error("BidExpired")]
     BidExpired {},
 
+    #[error("BidNotFound")]
+    BidNotFound {},
+
+    #[error("BidNotStale")]
+    BidNotStale {},
+
+    #[error("Bid must be higher than the current highest bid")]
+    BidTooLow {},
+
+    #[error("Highest bid is locked until the auction is settled")]
+    AuctionBidLocked {},
+
+    #[error("Auction has a bid and can't be changed until it is settled")]
+    AuctionLocked {},
+
+    #[error("Auction bids can not expire before the auction ends")]
+    BidExpiresBeforeAuctionEnd {},
+
+    #[error("AuctionNotEnded")]
+    AuctionNotEnded {},
+
+    #[error("Ask is not an auction")]
+    NotAuction {},
+
+    #[error("Reserve price not met")]
+    ReservePriceNotMet {},
+
+    #[error("Bid increment too small, min bid is {0}")]
+    BidIncrementTooSmall(Uint128),
+
+    #[error("Invalid Dutch auction: {0}")]
+    InvalidDutchAuction(String),
+
     #[error("UnauthorizedOwner")]
     UnauthorizedOwner {},
 
+    #[error("UnauthorizedOperator")]
+    UnauthorizedOperator {},
+
+    #[error("Token id {0} exceeds the listable token id range")]
+    TokenIdOutOfRange(u64),
+
+    #[error("UnauthorizedCreator")]
+    UnauthorizedCreator {},
+
+    #[error("InvalidRoyaltyBps: {0}")]
+    InvalidRoyaltyBps(u64),
+
+    #[error("Royalty shares must be non-zero and within the max royalty")]
+    InvalidRoyalties {},
+
+    #[error("Recipient {0} is listed more than once")]
+    DuplicateRecipient(String),
+
     #[error("Collection not tradable yet")]
     CollectionNotTradable {},
+
+    #[error("InvalidSignature")]
+    InvalidSignature {},
+
+    #[error("VoucherExpired")]
+    VoucherExpired {},
+
+    #[error("VoucherAlreadyRedeemed")]
+    VoucherAlreadyRedeemed {},
+
+    #[error("Order cancelled or already filled")]
+    OrderCancelledOrFilled {},
+
+    #[error("Operator already registered")]
+    OperatorAlreadyRegistered {},
+
+    #[error("Operator not registered")]
+    OperatorNotRegistered {},
+
+    #[error("Payment asset already registered with a different asset type")]
+    PaymentAssetTypeMismatch {},
+
+    #[error("Payment asset not registered")]
+    PaymentAssetNotRegistered {},
+
+    #[error("Asset params not set for {0}")]
+    AssetParamsNotFound(String),
+
+    #[error("Fee distribution shares must add up to 10000 bps")]
+    InvalidFeeDistribution {},
 }
@@ -4,10 +4,13 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use cosmwasm_std::{
-    to_binary, Addr, BlockInfo, CosmosMsg, StdError, StdResult, Timestamp, WasmMsg,
+    to_binary, Addr, Api, BlockInfo, CosmosMsg, StdError, StdResult, Timestamp, WasmMsg,
 };
 
-use crate::msg::ExecuteMsg;
+use crate::contract::MAX_FEE_BPS;
+use crate::msg::{ExecuteMsg, FeeDistributionMsg};
+use crate::state::{FeeDistribution, FeeShare};
+use crate::ContractError;
 
 /// CwTemplateContract is a wrapper around Addr that provides a lot of helpers
 /// for working with this.
@@ -69,3 +72,51 @@ impl ExpiryRange {
         Ok(())
     }
 }
+
+/// Validates a fee distribution, its shares and burn must add up to 10000 bps
+pub fn fee_distribution_validate(
+    api: &dyn Api,
+    fee_distribution: FeeDistributionMsg,
+) -> Result<FeeDistribution, ContractError> {
+    let shares = fee_distribution
+        .shares
+        .into_iter()
+        .map(|share| {
+            Ok(FeeShare {
+                recipient: api.addr_validate(&share.recipient)?,
+                share_bps: share.share_bps,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let total_bps = shares
+        .iter()
+        .try_fold(fee_distribution.burn_bps, |total, share| {
+            total.checked_add(share.share_bps)
+        });
+    if total_bps != Some(MAX_FEE_BPS) || shares.iter().any(|share| share.share_bps == 0) {
+        return Err(ContractError::InvalidFeeDistribution {});
+    }
+    for (i, share) in shares.iter().enumerate() {
+        if shares[..i]
+            .iter()
+            .any(|other| other.recipient == share.recipient)
+        {
+            return Err(ContractError::DuplicateRecipient(
+                share.recipient.to_string(),
+            ));
+        }
+    }
+
+    Ok(FeeDistribution {
+        shares,
+        burn_bps: fee_distribution.burn_bps,
+    })
+}
+
+/// Validates a list of addresses
+pub fn map_validate(api: &dyn Api, addresses: &[String]) -> StdResult<Vec<Addr>> {
+    addresses
+        .iter()
+        .map(|addr| api.addr_validate(addr))
+        .collect()
+}
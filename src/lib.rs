@@ -2,6 +2,8 @@ pub mod contract;
 mod error;
 pub mod helpers;
 pub mod msg;
+pub mod query;
 pub mod state;
+pub mod sudo;
 
 pub use crate::error::ContractError;
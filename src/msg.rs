@@ -1,9 +1,79 @@
-use crate::state::{Ask, Bid, SaleType, TokenId};
+use crate::helpers::ExpiryRange;
+use crate::state::{
+    Ask, AssetParams, AssetType, Bid, CollectionBid, FeeDestination, FeeDistribution, PaymentAsset,
+    RoyaltyShare, SaleType, SudoParams, TokenId, TokenInfo,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{to_binary, Addr, Binary, Coin, StdResult, Timestamp};
+use cosmwasm_std::{to_binary, Addr, Binary, Coin, StdResult, Timestamp, Uint128};
+use cw20::Cw20ReceiveMsg;
+use cw_utils::Duration;
+use sg_controllers::HooksResponse;
 
 #[cw_serde]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    /// Name of the natively minted token collection
+    pub name: String,
+    /// Symbol of the natively minted token collection
+    pub symbol: String,
+    /// Address allowed to mint native tokens
+    pub minter: String,
+    /// Denom accepted for asks, bids and fees
+    pub denom: String,
+    /// Network fee for winning bids, split according to `fee_distribution`
+    /// 0.25% = 25, 0.5% = 50, 1% = 100, 2.5% = 250
+    pub trading_fee_bps: u64,
+    /// Valid time range for Asks
+    /// (min, max) in seconds
+    pub ask_expiry: ExpiryRange,
+    /// Valid time range for Bids
+    /// (min, max) in seconds
+    pub bid_expiry: ExpiryRange,
+    /// Operators are entites that are responsible for maintaining the active state of Asks.
+    /// They listen to NFT transfer events, and update the active state of Asks.
+    pub operators: Vec<String>,
+    /// Max basis points for the finders fee
+    pub max_finders_fee_bps: u64,
+    /// Min value for bids and asks
+    pub min_price: Uint128,
+    /// Duration after expiry when a bid becomes stale (in seconds)
+    pub stale_bid_duration: Duration,
+    /// Stale bid removal reward
+    pub bid_removal_reward_bps: u64,
+    /// Listing fee to reduce spam
+    pub listing_fee: Uint128,
+    /// Bids within this many seconds of an auction's end extend it (0 disables, max a day)
+    pub auction_extension_window: u64,
+    /// Seconds an auction is extended by after a late bid (max a day)
+    pub auction_extension_duration: u64,
+    /// Min amount a bid on an auction has to beat the highest bid by
+    pub min_bid_increment_bps: u64,
+    /// Where listing fees are sent
+    pub listing_fee_destination: FeeDestination,
+    /// Seconds after listing during which cancelling an ask refunds the listing fee
+    pub listing_fee_grace_period: u64,
+    /// How the network fee is split between recipients and burned
+    pub fee_distribution: FeeDistributionMsg,
+    /// Max total royalties paid on a sale, registered with the marketplace or set by the collection
+    pub max_royalty_bps: u64,
+}
+
+#[cw_serde]
+pub struct FeeShareMsg {
+    pub recipient: String,
+    pub share_bps: u64,
+}
+
+#[cw_serde]
+pub struct FeeDistributionMsg {
+    pub shares: Vec<FeeShareMsg>,
+    pub burn_bps: u64,
+}
+
+#[cw_serde]
+pub struct RoyaltyShareMsg {
+    pub recipient: String,
+    pub share_bps: u64,
+}
 
 #[cw_serde]
 pub enum ExecuteMsg {
@@ -16,6 +86,23 @@ pub enum ExecuteMsg {
         reserve_for: Option<String>,
         finders_fee_bps: Option<u64>,
         expires: Timestamp,
+        /// Hidden min price an auction has to reach for bids to be accepted
+        reserve_price: Option<Coin>,
+        /// Overrides the global min bid increment for an auction
+        min_bid_increment_bps: Option<u64>,
+    },
+    /// List an NFT in a Dutch auction, its price declines linearly from `start_price`
+    /// to `end_price` between `start_time` and `end_time`. Bought via `BuyNow`.
+    SetDutchAuction {
+        collection: String,
+        token_id: TokenId,
+        start_price: Coin,
+        end_price: Coin,
+        start_time: Timestamp,
+        end_time: Timestamp,
+        funds_recipient: Option<String>,
+        finders_fee_bps: Option<u64>,
+        expires: Timestamp,
     },
     SetBid {
         collection: String,
@@ -38,7 +125,234 @@ pub enum ExecuteMsg {
         bidder: String,
         finder: Option<String>,
     },
+    /// Remove an existing ask, can only be called by the seller
+    RemoveAsk {
+        collection: String,
+        token_id: TokenId,
+    },
+    /// Update the price of an existing ask, can only be called by the seller
+    UpdateAskPrice {
+        collection: String,
+        token_id: TokenId,
+        price: Coin,
+    },
+    /// Re-check the owner and approval of an ask and update its active state accordingly.
+    /// Can be called by anyone.
+    SyncAsk {
+        collection: String,
+        token_id: TokenId,
+    },
+    /// Set the active state of an ask, can only be called by an operator
+    UpdateAskState {
+        collection: String,
+        token_id: TokenId,
+        active: bool,
+    },
+    /// Remove an expired ask, can only be called by an operator
+    RemoveStaleAsk {
+        collection: String,
+        token_id: TokenId,
+    },
+    /// Scan a page of asks for a collection and remove the expired ones,
+    /// can only be called by an operator
+    RemoveStaleAsks {
+        collection: String,
+        start_after: Option<TokenId>,
+        limit: Option<u32>,
+    },
+    /// Remove an existing bid and refund the escrowed funds to the bidder
+    RemoveBid {
+        collection: String,
+        token_id: TokenId,
+    },
+    /// Mint a new native token, can only be called by the minter
+    Mint {
+        owner: String,
+        token_uri: Option<String>,
+        base_price: Vec<Coin>,
+    },
+    /// Mint a new native token to the minter and list it for sale in one transaction.
+    /// `price` defaults to the `base_price` in the accepted denom.
+    MintAndList {
+        token_uri: Option<String>,
+        base_price: Vec<Coin>,
+        sale_type: SaleType,
+        price: Option<Coin>,
+        funds_recipient: Option<String>,
+        reserve_for: Option<String>,
+        finders_fee_bps: Option<u64>,
+        expires: Timestamp,
+    },
+    /// Mint a token from a creator-signed voucher, paying the voucher price to the creator
+    RedeemVoucher {
+        voucher: MintVoucher,
+        signature: Binary,
+    },
+    /// Buy an NFT through an ask signed off-chain by the seller
+    FillSignedAsk {
+        order: SignedAsk,
+        signature: Binary,
+        finder: Option<String>,
+    },
+    /// Invalidate off-chain signed asks of the sender by nonce
+    CancelSignedOrders { nonces: Vec<u64> },
+    /// Close an auction that has reached its expiry, selling to the highest bidder
+    /// if the ask price was met. Can be called by anyone.
+    SettleAuction {
+        collection: String,
+        token_id: TokenId,
+    },
+    /// Place a bid (limit order) across an entire collection
+    SetCollectionBid {
+        collection: String,
+        finders_fee_bps: Option<u64>,
+        expires: Timestamp,
+    },
+    /// Remove an existing collection bid and refund the escrowed funds to the bidder
+    RemoveCollectionBid { collection: String },
+    /// Accept a collection bid with any token the sender owns in the collection
+    AcceptCollectionBid {
+        collection: String,
+        token_id: TokenId,
+        bidder: String,
+        finder: Option<String>,
+    },
+    /// Remove a bid that has been expired for longer than `stale_bid_duration`.
+    /// The operator is rewarded with `bid_removal_reward_percent` of the bid.
+    RemoveStaleBid {
+        collection: String,
+        token_id: TokenId,
+        bidder: String,
+    },
+    /// Place a bid paid in an accepted CW20 token, see `ReceiveMsg`
+    Receive(Cw20ReceiveMsg),
+    /// Register royalty recipients for a collection, used instead of the sg721 royalty.
+    /// Can only be called by the collection creator, an empty list removes them.
+    SetRoyalties {
+        collection: String,
+        royalties: Vec<RoyaltyShareMsg>,
+    },
+}
+
+/// Messages that can be sent along with CW20 tokens to place bids
+#[cw_serde]
+pub enum ReceiveMsg {
+    SetBid {
+        collection: String,
+        token_id: TokenId,
+        expires: Timestamp,
+        sale_type: SaleType,
+        finder: Option<String>,
+        finders_fee_bps: Option<u64>,
+    },
+    BuyNow {
+        collection: String,
+        token_id: TokenId,
+        expires: Timestamp,
+        finder: Option<String>,
+        finders_fee_bps: Option<u64>,
+    },
+    SetCollectionBid {
+        collection: String,
+        finders_fee_bps: Option<u64>,
+        expires: Timestamp,
+    },
+}
+
+#[cw_serde]
+#[allow(clippy::large_enum_variant)]
+pub enum SudoMsg {
+    /// Update the contract parameters
+    /// Can only be called by governance
+    UpdateParams {
+        trading_fee_bps: Option<u64>,
+        ask_expiry: Option<ExpiryRange>,
+        bid_expiry: Option<ExpiryRange>,
+        operators: Option<Vec<String>>,
+        max_finders_fee_bps: Option<u64>,
+        min_price: Option<Uint128>,
+        stale_bid_duration: Option<Duration>,
+        bid_removal_reward_bps: Option<u64>,
+        listing_fee: Option<Uint128>,
+        auction_extension_window: Option<u64>,
+        auction_extension_duration: Option<u64>,
+        min_bid_increment_bps: Option<u64>,
+        listing_fee_destination: Option<FeeDestination>,
+        listing_fee_grace_period: Option<u64>,
+        max_royalty_bps: Option<u64>,
+    },
+    /// Add a new operator
+    AddOperator { operator: String },
+    /// Remove operator
+    RemoveOperator { operator: String },
+    /// Add a new hook to be informed of all asks
+    AddAskHook { hook: String },
+    /// Remove a ask hook
+    RemoveAskHook { hook: String },
+    /// Add a new hook to be informed of all bids
+    AddBidHook { hook: String },
+    /// Remove a bid hook
+    RemoveBidHook { hook: String },
+    /// Add a new hook to be informed of all trades
+    AddSaleHook { hook: String },
+    /// Remove a trade hook
+    RemoveSaleHook { hook: String },
+    /// Add a new hook to be informed of all collection bids
+    AddCollectionBidHook { hook: String },
+    /// Remove a collection bid hook
+    RemoveCollectionBidHook { hook: String },
+    /// Accept a native denom or CW20 token (by contract address) for payments
+    AddPaymentAsset {
+        denom: String,
+        asset_type: AssetType,
+    },
+    /// Stop accepting an asset for new orders
+    RemovePaymentAsset { denom: String },
+    /// Set the min price, listing fee and optional trading fee of a payment asset.
+    /// CW20 assets can't set a listing fee, their asks pay the global one.
+    SetAssetParams {
+        denom: String,
+        min_price: Uint128,
+        listing_fee: Uint128,
+        trading_fee_bps: Option<u64>,
+    },
+    /// Remove the parameters of a payment asset, falling back to the global ones
+    RemoveAssetParams { denom: String },
+    /// Replace how the network fee is split, shares and burn must add up to 10000 bps
+    UpdateFeeDistribution {
+        fee_distribution: FeeDistributionMsg,
+    },
+}
+
+/// Off-chain mint authorization signed by a creator, redeemed by the first buyer
+#[cw_serde]
+pub struct MintVoucher {
+    pub token_uri: Option<String>,
+    /// Paid with the funds sent to `RedeemVoucher`, so it must be a native denom
+    pub price: Coin,
+    /// Compressed secp256k1 public key of the creator, who receives the payment
+    pub creator_pubkey: Binary,
+    pub nonce: u64,
+    pub expires: Timestamp,
 }
+
+/// Fixed price ask signed off-chain by the seller, filled on-chain by the buyer
+#[cw_serde]
+pub struct SignedAsk {
+    pub collection: String,
+    pub token_id: TokenId,
+    pub seller: String,
+    /// Paid with the funds sent to `FillSignedAsk`, so it must be a native denom
+    pub price: Coin,
+    pub funds_recipient: Option<String>,
+    pub reserve_for: Option<String>,
+    pub finders_fee_bps: Option<u64>,
+    pub expires_at: Timestamp,
+    pub nonce: u64,
+    /// Compressed secp256k1 public key of the seller
+    pub seller_pubkey: Binary,
+}
+
 #[cw_serde]
 pub struct BidHookMsg {
     pub bid: Bid,
@@ -73,6 +387,35 @@ pub enum HookAction {
     Delete,
 }
 
+#[cw_serde]
+pub struct CollectionBidHookMsg {
+    pub collection_bid: CollectionBid,
+}
+
+impl CollectionBidHookMsg {
+    pub fn new(collection_bid: CollectionBid) -> Self {
+        CollectionBidHookMsg { collection_bid }
+    }
+
+    /// serializes the message
+    pub fn into_binary(self, action: HookAction) -> StdResult<Binary> {
+        let msg = match action {
+            HookAction::Create => CollectionBidExecuteMsg::CollectionBidCreatedHook(self),
+            HookAction::Update => CollectionBidExecuteMsg::CollectionBidUpdatedHook(self),
+            HookAction::Delete => CollectionBidExecuteMsg::CollectionBidDeletedHook(self),
+        };
+        to_binary(&msg)
+    }
+}
+
+// This is just a helper to properly serialize the above message
+#[cw_serde]
+pub enum CollectionBidExecuteMsg {
+    CollectionBidCreatedHook(CollectionBidHookMsg),
+    CollectionBidUpdatedHook(CollectionBidHookMsg),
+    CollectionBidDeletedHook(CollectionBidHookMsg),
+}
+
 #[cw_serde]
 pub struct AskHookMsg {
     pub ask: Ask,
@@ -141,6 +484,348 @@ pub enum AskHookExecuteMsg {
     AskDeletedHook(AskHookMsg),
 }
 
+/// Offset for ask pagination
+#[cw_serde]
+pub struct AskOffset {
+    pub price: Uint128,
+    pub token_id: TokenId,
+}
+
+impl AskOffset {
+    pub fn new(price: Uint128, token_id: TokenId) -> Self {
+        AskOffset { price, token_id }
+    }
+}
+
+/// Offset for bid pagination
+#[cw_serde]
+pub struct BidOffset {
+    pub price: Uint128,
+    pub token_id: TokenId,
+    pub bidder: Addr,
+}
+
+impl BidOffset {
+    pub fn new(price: Uint128, token_id: TokenId, bidder: Addr) -> Self {
+        BidOffset {
+            price,
+            token_id,
+            bidder,
+        }
+    }
+}
+
+/// Offset for bid pagination by expiration
+#[cw_serde]
+pub struct BidExpirationOffset {
+    pub expires_at: Timestamp,
+    pub collection: String,
+    pub token_id: TokenId,
+}
+
+impl BidExpirationOffset {
+    pub fn new(expires_at: Timestamp, collection: String, token_id: TokenId) -> Self {
+        BidExpirationOffset {
+            expires_at,
+            collection,
+            token_id,
+        }
+    }
+}
+
+/// Offset for collection bid pagination
+#[cw_serde]
+pub struct CollectionBidOffset {
+    pub price: Uint128,
+    pub bidder: String,
+}
+
+impl CollectionBidOffset {
+    pub fn new(price: Uint128, bidder: String) -> Self {
+        CollectionBidOffset { price, bidder }
+    }
+}
+
+/// Offset for collection pagination
+#[cw_serde]
+pub struct CollectionOffset {
+    pub collection: String,
+    pub token_id: TokenId,
+}
+
+impl CollectionOffset {
+    pub fn new(collection: String, token_id: TokenId) -> Self {
+        CollectionOffset {
+            collection,
+            token_id,
+        }
+    }
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
-pub enum QueryMsg {}
+pub enum QueryMsg {
+    /// Get the current ask for specific NFT
+    #[returns(AskResponse)]
+    Ask {
+        collection: String,
+        token_id: TokenId,
+    },
+    /// Get the price an NFT can be bought for at the current block,
+    /// following the schedule for Dutch auctions
+    #[returns(AskCurrentPriceResponse)]
+    AskCurrentPrice {
+        collection: String,
+        token_id: TokenId,
+    },
+    /// Get all asks for a collection
+    #[returns(AsksResponse)]
+    Asks {
+        collection: String,
+        include_inactive: Option<bool>,
+        start_after: Option<TokenId>,
+        limit: Option<u32>,
+    },
+    /// Get all asks for a collection, sorted by price.
+    /// Dutch auctions are sorted by their start price, not their current price.
+    #[returns(AsksResponse)]
+    AsksSortedByPrice {
+        collection: String,
+        include_inactive: Option<bool>,
+        start_after: Option<AskOffset>,
+        limit: Option<u32>,
+        descending: Option<bool>,
+    },
+    /// Get all asks by seller
+    #[returns(AsksResponse)]
+    AsksBySeller {
+        seller: String,
+        include_inactive: Option<bool>,
+        start_after: Option<CollectionOffset>,
+        limit: Option<u32>,
+    },
+    /// Count of all asks for a collection
+    #[returns(AskCountResponse)]
+    AskCount { collection: String },
+    /// Get data for a specific bid
+    #[returns(BidResponse)]
+    Bid {
+        collection: String,
+        token_id: TokenId,
+        bidder: String,
+    },
+    /// Get all bids for a specific NFT
+    #[returns(BidsResponse)]
+    Bids {
+        collection: String,
+        token_id: TokenId,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Get all bids by a bidder
+    #[returns(BidsResponse)]
+    BidsByBidder {
+        bidder: String,
+        start_after: Option<CollectionOffset>,
+        limit: Option<u32>,
+    },
+    /// Get all bids by a bidder, sorted by expiration
+    #[returns(BidsResponse)]
+    BidsByBidderSortedByExpiration {
+        bidder: String,
+        start_after: Option<BidExpirationOffset>,
+        limit: Option<u32>,
+    },
+    /// Get all bids for a collection, sorted by price
+    #[returns(BidsResponse)]
+    BidsSortedByPrice {
+        collection: String,
+        start_after: Option<BidOffset>,
+        limit: Option<u32>,
+        descending: Option<bool>,
+    },
+    /// Get data for a specific collection bid
+    #[returns(CollectionBidResponse)]
+    CollectionBid { collection: String, bidder: String },
+    /// Get all collection bids for a collection, sorted by price
+    #[returns(CollectionBidsResponse)]
+    CollectionBidsSortedByPrice {
+        collection: String,
+        start_after: Option<CollectionBidOffset>,
+        limit: Option<u32>,
+        descending: Option<bool>,
+    },
+    /// Get all collection bids by a bidder
+    #[returns(CollectionBidsResponse)]
+    CollectionBidsByBidder {
+        bidder: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Get a natively minted token
+    #[returns(TokenInfo)]
+    Token { token_id: u64 },
+    /// Get all natively minted tokens owned by an address
+    #[returns(TokensResponse)]
+    Tokens {
+        owner: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Count of all natively minted tokens
+    #[returns(NumTokensResponse)]
+    NumTokens {},
+    /// Get the config for the contract
+    #[returns(ParamsResponse)]
+    Params {},
+    /// Show all registered ask hooks
+    #[returns(HooksResponse)]
+    AskHooks {},
+    /// Show all registered bid hooks
+    #[returns(HooksResponse)]
+    BidHooks {},
+    /// Show all registered sale hooks
+    #[returns(HooksResponse)]
+    SaleHooks {},
+    /// Show all registered collection bid hooks
+    #[returns(HooksResponse)]
+    CollectionBidHooks {},
+    /// Show all registered payment assets
+    #[returns(PaymentAssetsResponse)]
+    PaymentAssets {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Show the parameters set for individual payment assets
+    #[returns(AssetParamsResponse)]
+    AssetParams {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Show how the network fee is distributed
+    #[returns(FeeDistributionResponse)]
+    FeeDistribution {},
+    /// Show the royalties registered for a collection
+    #[returns(RoyaltiesResponse)]
+    Royalties { collection: String },
+    /// Simulate how a sale at `price` would be paid out,
+    /// so sellers can see their exact proceeds before listing
+    #[returns(SimulatePayoutResponse)]
+    SimulatePayout {
+        collection: String,
+        token_id: TokenId,
+        price: Coin,
+        finder: Option<String>,
+        finders_fee_bps: Option<u64>,
+    },
+}
+
+#[cw_serde]
+pub struct AskResponse {
+    pub ask: Option<Ask>,
+}
+
+#[cw_serde]
+pub struct AsksResponse {
+    pub asks: Vec<Ask>,
+}
+
+#[cw_serde]
+pub struct RoyaltiesResponse {
+    pub royalties: Vec<RoyaltyShare>,
+}
+
+#[cw_serde]
+pub struct AskCurrentPriceResponse {
+    pub price: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct AskCountResponse {
+    pub count: u32,
+}
+
+#[cw_serde]
+pub struct BidResponse {
+    pub bid: Option<Bid>,
+}
+
+#[cw_serde]
+pub struct BidsResponse {
+    pub bids: Vec<Bid>,
+}
+
+#[cw_serde]
+pub struct CollectionBidResponse {
+    pub bid: Option<CollectionBid>,
+}
+
+#[cw_serde]
+pub struct CollectionBidsResponse {
+    pub bids: Vec<CollectionBid>,
+}
+
+#[cw_serde]
+pub struct TokensResponse {
+    pub tokens: Vec<TokenInfo>,
+}
+
+#[cw_serde]
+pub struct NumTokensResponse {
+    pub count: u64,
+}
+
+#[cw_serde]
+pub struct ParamsResponse {
+    pub params: SudoParams,
+}
+
+#[cw_serde]
+pub struct PaymentAssetsResponse {
+    pub assets: Vec<(String, PaymentAsset)>,
+}
+
+/// How a sale price is split between the parties
+#[cw_serde]
+pub struct PayoutBreakdown {
+    pub network_fee: Coin,
+    pub royalties: Vec<RoyaltyPayout>,
+    pub finders_fee: Coin,
+    pub seller_amount: Coin,
+}
+
+#[cw_serde]
+pub struct RoyaltyPayout {
+    pub recipient: Addr,
+    pub amount: Coin,
+}
+
+/// Royalty query of cw2981 collections, sent as a cw721 extension query
+#[cw_serde]
+pub enum Cw2981QueryMsg {
+    RoyaltyInfo {
+        token_id: String,
+        sale_price: Uint128,
+    },
+}
+
+#[cw_serde]
+pub struct RoyaltiesInfoResponse {
+    pub address: String,
+    pub royalty_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulatePayoutResponse {
+    pub payout: PayoutBreakdown,
+}
+
+#[cw_serde]
+pub struct FeeDistributionResponse {
+    pub fee_distribution: FeeDistribution,
+}
+
+#[cw_serde]
+pub struct AssetParamsResponse {
+    pub params: Vec<(String, AssetParams)>,
+}
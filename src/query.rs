@@ -0,0 +1,663 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coin, to_binary, Addr, Binary, Coin, Decimal, Deps, Env, Order, StdError, StdResult,
+};
+use cw_storage_plus::Bound;
+use cw_utils::maybe_addr;
+
+use crate::contract::{
+    calculate_payout, collection_royalties, query_config, trading_fee_percent, MAX_FEE_BPS,
+};
+use crate::error::ContractError;
+use crate::msg::{
+    AskCountResponse, AskCurrentPriceResponse, AskOffset, AskResponse, AsksResponse,
+    AssetParamsResponse, BidExpirationOffset, BidOffset, BidResponse, BidsResponse,
+    CollectionBidOffset, CollectionBidResponse, CollectionBidsResponse, CollectionOffset,
+    FeeDistributionResponse, NumTokensResponse, ParamsResponse, PaymentAssetsResponse, QueryMsg,
+    RoyaltiesResponse, SimulatePayoutResponse, TokensResponse,
+};
+use crate::state::{
+    ask_key, asks, bid_key, bids, collection_bid_key, collection_bids, tokens, TokenId, TokenInfo,
+    ASK_HOOKS, ASSET_PARAMS, BID_HOOKS, COLLECTION_BID_HOOKS, FEE_DISTRIBUTION, PAYMENT_ASSETS,
+    ROYALTIES, SALE_HOOKS, SUDO_PARAMS,
+};
+
+// Query limits
+const DEFAULT_QUERY_LIMIT: u32 = 10;
+const MAX_QUERY_LIMIT: u32 = 100;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let api = deps.api;
+
+    match msg {
+        QueryMsg::Ask {
+            collection,
+            token_id,
+        } => to_binary(&query_ask(deps, api.addr_validate(&collection)?, token_id)?),
+        QueryMsg::AskCurrentPrice {
+            collection,
+            token_id,
+        } => to_binary(&query_ask_current_price(
+            deps,
+            env,
+            api.addr_validate(&collection)?,
+            token_id,
+        )?),
+        QueryMsg::Asks {
+            collection,
+            include_inactive,
+            start_after,
+            limit,
+        } => to_binary(&query_asks(
+            deps,
+            api.addr_validate(&collection)?,
+            include_inactive,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::AsksSortedByPrice {
+            collection,
+            include_inactive,
+            start_after,
+            limit,
+            descending,
+        } => to_binary(&query_asks_sorted_by_price(
+            deps,
+            api.addr_validate(&collection)?,
+            include_inactive,
+            start_after,
+            limit,
+            descending,
+        )?),
+        QueryMsg::AsksBySeller {
+            seller,
+            include_inactive,
+            start_after,
+            limit,
+        } => to_binary(&query_asks_by_seller(
+            deps,
+            api.addr_validate(&seller)?,
+            include_inactive,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::AskCount { collection } => {
+            to_binary(&query_ask_count(deps, api.addr_validate(&collection)?)?)
+        }
+        QueryMsg::Bid {
+            collection,
+            token_id,
+            bidder,
+        } => to_binary(&query_bid(
+            deps,
+            api.addr_validate(&collection)?,
+            token_id,
+            api.addr_validate(&bidder)?,
+        )?),
+        QueryMsg::Bids {
+            collection,
+            token_id,
+            start_after,
+            limit,
+        } => to_binary(&query_bids(
+            deps,
+            api.addr_validate(&collection)?,
+            token_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::BidsByBidder {
+            bidder,
+            start_after,
+            limit,
+        } => to_binary(&query_bids_by_bidder(
+            deps,
+            api.addr_validate(&bidder)?,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::BidsByBidderSortedByExpiration {
+            bidder,
+            start_after,
+            limit,
+        } => to_binary(&query_bids_by_bidder_sorted_by_expiration(
+            deps,
+            api.addr_validate(&bidder)?,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::BidsSortedByPrice {
+            collection,
+            start_after,
+            limit,
+            descending,
+        } => to_binary(&query_bids_sorted_by_price(
+            deps,
+            api.addr_validate(&collection)?,
+            start_after,
+            limit,
+            descending,
+        )?),
+        QueryMsg::CollectionBid { collection, bidder } => to_binary(&query_collection_bid(
+            deps,
+            api.addr_validate(&collection)?,
+            api.addr_validate(&bidder)?,
+        )?),
+        QueryMsg::CollectionBidsSortedByPrice {
+            collection,
+            start_after,
+            limit,
+            descending,
+        } => to_binary(&query_collection_bids_sorted_by_price(
+            deps,
+            api.addr_validate(&collection)?,
+            start_after,
+            limit,
+            descending,
+        )?),
+        QueryMsg::CollectionBidsByBidder {
+            bidder,
+            start_after,
+            limit,
+        } => to_binary(&query_collection_bids_by_bidder(
+            deps,
+            api.addr_validate(&bidder)?,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Token { token_id } => to_binary(&query_token(deps, token_id)?),
+        QueryMsg::Tokens {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_tokens(
+            deps,
+            api.addr_validate(&owner)?,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::NumTokens {} => to_binary(&query_num_tokens(deps)?),
+        QueryMsg::Params {} => to_binary(&query_params(deps)?),
+        QueryMsg::AskHooks {} => to_binary(&ASK_HOOKS.query_hooks(deps)?),
+        QueryMsg::BidHooks {} => to_binary(&BID_HOOKS.query_hooks(deps)?),
+        QueryMsg::SaleHooks {} => to_binary(&SALE_HOOKS.query_hooks(deps)?),
+        QueryMsg::CollectionBidHooks {} => to_binary(&COLLECTION_BID_HOOKS.query_hooks(deps)?),
+        QueryMsg::PaymentAssets { start_after, limit } => {
+            to_binary(&query_payment_assets(deps, start_after, limit)?)
+        }
+        QueryMsg::AssetParams { start_after, limit } => {
+            to_binary(&query_asset_params(deps, start_after, limit)?)
+        }
+        QueryMsg::FeeDistribution {} => to_binary(&query_fee_distribution(deps)?),
+        QueryMsg::Royalties { collection } => {
+            to_binary(&query_royalties(deps, api.addr_validate(&collection)?)?)
+        }
+        QueryMsg::SimulatePayout {
+            collection,
+            token_id,
+            price,
+            finder,
+            finders_fee_bps,
+        } => to_binary(&query_simulate_payout(
+            deps,
+            api.addr_validate(&collection)?,
+            token_id,
+            price,
+            maybe_addr(api, finder)?,
+            finders_fee_bps,
+        )?),
+    }
+}
+
+fn query_limit(limit: Option<u32>) -> usize {
+    limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize
+}
+
+fn query_order(descending: Option<bool>) -> Order {
+    match descending {
+        Some(true) => Order::Descending,
+        _ => Order::Ascending,
+    }
+}
+
+pub fn query_ask(deps: Deps, collection: Addr, token_id: TokenId) -> StdResult<AskResponse> {
+    let ask = asks().may_load(deps.storage, ask_key(&collection, token_id))?;
+
+    Ok(AskResponse { ask })
+}
+
+pub fn query_ask_current_price(
+    deps: Deps,
+    env: Env,
+    collection: Addr,
+    token_id: TokenId,
+) -> StdResult<AskCurrentPriceResponse> {
+    let price = asks()
+        .may_load(deps.storage, ask_key(&collection, token_id))?
+        .map(|ask| coin(ask.current_price(env.block.time).u128(), ask.denom));
+
+    Ok(AskCurrentPriceResponse { price })
+}
+
+pub fn query_asks(
+    deps: Deps,
+    collection: Addr,
+    include_inactive: Option<bool>,
+    start_after: Option<TokenId>,
+    limit: Option<u32>,
+) -> StdResult<AsksResponse> {
+    let limit = query_limit(limit);
+    let include_inactive = include_inactive.unwrap_or(false);
+    let start = start_after.map(|token_id| Bound::exclusive(ask_key(&collection, token_id)));
+
+    let asks = asks()
+        .idx
+        .collection
+        .prefix(collection)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, ask)) => include_inactive || ask.is_active,
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AsksResponse { asks })
+}
+
+pub fn query_asks_sorted_by_price(
+    deps: Deps,
+    collection: Addr,
+    include_inactive: Option<bool>,
+    start_after: Option<AskOffset>,
+    limit: Option<u32>,
+    descending: Option<bool>,
+) -> StdResult<AsksResponse> {
+    let limit = query_limit(limit);
+    let include_inactive = include_inactive.unwrap_or(false);
+    let order = query_order(descending);
+    let bound = start_after.map(|offset| {
+        Bound::exclusive((offset.price.u128(), ask_key(&collection, offset.token_id)))
+    });
+    let (start, end) = match order {
+        Order::Ascending => (bound, None),
+        Order::Descending => (None, bound),
+    };
+
+    let asks = asks()
+        .idx
+        .collection_price
+        .sub_prefix(collection)
+        .range(deps.storage, start, end, order)
+        .filter(|item| match item {
+            Ok((_, ask)) => include_inactive || ask.is_active,
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AsksResponse { asks })
+}
+
+pub fn query_asks_by_seller(
+    deps: Deps,
+    seller: Addr,
+    include_inactive: Option<bool>,
+    start_after: Option<CollectionOffset>,
+    limit: Option<u32>,
+) -> StdResult<AsksResponse> {
+    let limit = query_limit(limit);
+    let include_inactive = include_inactive.unwrap_or(false);
+    let start = match start_after {
+        Some(offset) => {
+            let collection = deps.api.addr_validate(&offset.collection)?;
+            Some(Bound::exclusive(ask_key(&collection, offset.token_id)))
+        }
+        None => None,
+    };
+
+    let asks = asks()
+        .idx
+        .seller
+        .prefix(seller)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, ask)) => include_inactive || ask.is_active,
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AsksResponse { asks })
+}
+
+pub fn query_ask_count(deps: Deps, collection: Addr) -> StdResult<AskCountResponse> {
+    let count = asks()
+        .idx
+        .collection
+        .prefix(collection)
+        .keys_raw(deps.storage, None, None, Order::Ascending)
+        .count() as u32;
+
+    Ok(AskCountResponse { count })
+}
+
+pub fn query_bid(
+    deps: Deps,
+    collection: Addr,
+    token_id: TokenId,
+    bidder: Addr,
+) -> StdResult<BidResponse> {
+    let bid = bids().may_load(deps.storage, bid_key(&collection, token_id, &bidder))?;
+
+    Ok(BidResponse { bid })
+}
+
+pub fn query_bids(
+    deps: Deps,
+    collection: Addr,
+    token_id: TokenId,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<BidsResponse> {
+    let limit = query_limit(limit);
+    let start = match start_after {
+        Some(bidder) => {
+            let bidder = deps.api.addr_validate(&bidder)?;
+            Some(Bound::exclusive(bid_key(&collection, token_id, &bidder)))
+        }
+        None => None,
+    };
+
+    let bids = bids()
+        .idx
+        .collection_token_id
+        .prefix((collection, token_id))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(BidsResponse { bids })
+}
+
+pub fn query_bids_by_bidder(
+    deps: Deps,
+    bidder: Addr,
+    start_after: Option<CollectionOffset>,
+    limit: Option<u32>,
+) -> StdResult<BidsResponse> {
+    let limit = query_limit(limit);
+    let start = match start_after {
+        Some(offset) => {
+            let collection = deps.api.addr_validate(&offset.collection)?;
+            Some(Bound::exclusive(bid_key(
+                &collection,
+                offset.token_id,
+                &bidder,
+            )))
+        }
+        None => None,
+    };
+
+    let bids = bids()
+        .idx
+        .bidder
+        .prefix(bidder)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(BidsResponse { bids })
+}
+
+pub fn query_bids_by_bidder_sorted_by_expiration(
+    deps: Deps,
+    bidder: Addr,
+    start_after: Option<BidExpirationOffset>,
+    limit: Option<u32>,
+) -> StdResult<BidsResponse> {
+    let limit = query_limit(limit);
+    let start = match start_after {
+        Some(offset) => {
+            let collection = deps.api.addr_validate(&offset.collection)?;
+            Some(Bound::exclusive((
+                offset.expires_at.seconds(),
+                bid_key(&collection, offset.token_id, &bidder),
+            )))
+        }
+        None => None,
+    };
+
+    let bids = bids()
+        .idx
+        .bidder_expires_at
+        .sub_prefix(bidder)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(BidsResponse { bids })
+}
+
+pub fn query_bids_sorted_by_price(
+    deps: Deps,
+    collection: Addr,
+    start_after: Option<BidOffset>,
+    limit: Option<u32>,
+    descending: Option<bool>,
+) -> StdResult<BidsResponse> {
+    let limit = query_limit(limit);
+    let order = query_order(descending);
+    let bound = start_after.map(|offset| {
+        Bound::exclusive((
+            offset.price.u128(),
+            bid_key(&collection, offset.token_id, &offset.bidder),
+        ))
+    });
+    let (start, end) = match order {
+        Order::Ascending => (bound, None),
+        Order::Descending => (None, bound),
+    };
+
+    let bids = bids()
+        .idx
+        .collection_price
+        .sub_prefix(collection)
+        .range(deps.storage, start, end, order)
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(BidsResponse { bids })
+}
+
+pub fn query_collection_bid(
+    deps: Deps,
+    collection: Addr,
+    bidder: Addr,
+) -> StdResult<CollectionBidResponse> {
+    let bid = collection_bids().may_load(deps.storage, collection_bid_key(&collection, &bidder))?;
+
+    Ok(CollectionBidResponse { bid })
+}
+
+pub fn query_collection_bids_sorted_by_price(
+    deps: Deps,
+    collection: Addr,
+    start_after: Option<CollectionBidOffset>,
+    limit: Option<u32>,
+    descending: Option<bool>,
+) -> StdResult<CollectionBidsResponse> {
+    let limit = query_limit(limit);
+    let order = query_order(descending);
+    let bound = match start_after {
+        Some(offset) => {
+            let bidder = deps.api.addr_validate(&offset.bidder)?;
+            Some(Bound::exclusive((
+                offset.price.u128(),
+                collection_bid_key(&collection, &bidder),
+            )))
+        }
+        None => None,
+    };
+    let (start, end) = match order {
+        Order::Ascending => (bound, None),
+        Order::Descending => (None, bound),
+    };
+
+    let bids = collection_bids()
+        .idx
+        .collection_price
+        .sub_prefix(collection)
+        .range(deps.storage, start, end, order)
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CollectionBidsResponse { bids })
+}
+
+pub fn query_collection_bids_by_bidder(
+    deps: Deps,
+    bidder: Addr,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CollectionBidsResponse> {
+    let limit = query_limit(limit);
+    let start = match start_after {
+        Some(collection) => {
+            let collection = deps.api.addr_validate(&collection)?;
+            Some(Bound::exclusive(collection_bid_key(&collection, &bidder)))
+        }
+        None => None,
+    };
+
+    let bids = collection_bids()
+        .idx
+        .bidder
+        .prefix(bidder)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CollectionBidsResponse { bids })
+}
+
+pub fn query_token(deps: Deps, token_id: u64) -> StdResult<TokenInfo> {
+    tokens().load(deps.storage, token_id)
+}
+
+pub fn query_tokens(
+    deps: Deps,
+    owner: Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let limit = query_limit(limit);
+    let start = start_after.map(Bound::exclusive);
+
+    let tokens = tokens()
+        .idx
+        .owner
+        .prefix(owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|item| item.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(TokensResponse { tokens })
+}
+
+pub fn query_num_tokens(deps: Deps) -> StdResult<NumTokensResponse> {
+    let count = query_config(deps)?.num_tokens;
+
+    Ok(NumTokensResponse { count })
+}
+
+pub fn query_params(deps: Deps) -> StdResult<ParamsResponse> {
+    let params = SUDO_PARAMS.load(deps.storage)?;
+
+    Ok(ParamsResponse { params })
+}
+
+pub fn query_fee_distribution(deps: Deps) -> StdResult<FeeDistributionResponse> {
+    let fee_distribution = FEE_DISTRIBUTION.load(deps.storage)?;
+
+    Ok(FeeDistributionResponse { fee_distribution })
+}
+
+pub fn query_payment_assets(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PaymentAssetsResponse> {
+    let limit = query_limit(limit);
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let assets = PAYMENT_ASSETS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PaymentAssetsResponse { assets })
+}
+
+pub fn query_asset_params(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AssetParamsResponse> {
+    let limit = query_limit(limit);
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let params = ASSET_PARAMS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AssetParamsResponse { params })
+}
+
+pub fn query_royalties(deps: Deps, collection: Addr) -> StdResult<RoyaltiesResponse> {
+    let royalties = ROYALTIES
+        .may_load(deps.storage, &collection)?
+        .unwrap_or_default();
+
+    Ok(RoyaltiesResponse { royalties })
+}
+
+pub fn query_simulate_payout(
+    deps: Deps,
+    collection: Addr,
+    token_id: TokenId,
+    price: Coin,
+    finder: Option<Addr>,
+    finders_fee_bps: Option<u64>,
+) -> StdResult<SimulatePayoutResponse> {
+    if let Some(finders_fee_bps) = finders_fee_bps {
+        let params = SUDO_PARAMS.load(deps.storage)?;
+        if Decimal::from_ratio(finders_fee_bps, MAX_FEE_BPS) > params.max_finders_fee_percent {
+            return Err(StdError::generic_err(
+                ContractError::InvalidFindersFeeBps(finders_fee_bps).to_string(),
+            ));
+        }
+    }
+
+    let payout = calculate_payout(
+        &price,
+        trading_fee_percent(deps.storage, &price.denom)?,
+        collection_royalties(deps, &collection, token_id, &price)?,
+        finder.and(finders_fee_bps),
+    )?;
+
+    Ok(SimulatePayoutResponse { payout })
+}
@@ -1,5 +1,5 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, BlockInfo, Coin, Decimal, Timestamp, Uint128};
+use cosmwasm_std::{Addr, Api, BlockInfo, Coin, Decimal, StdResult, Storage, Timestamp, Uint128};
 use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 use cw_utils::Duration;
 use sg_controllers::Hooks;
@@ -9,9 +9,10 @@ use std::fmt;
 
 pub type TokenId = u32;
 
+/// `*_percent` fields are fractions of the amount they apply to, 2% is stored as `0.02`
 #[cw_serde]
 pub struct SudoParams {
-    /// Fair Burn fee for winning bids
+    /// Network fee for winning bids, split according to `FEE_DISTRIBUTION`
     pub trading_fee_percent: Decimal,
     /// Valid time range for Asks
     /// (min, max) in seconds
@@ -24,21 +25,121 @@ pub struct SudoParams {
     pub operators: Vec<Addr>,
     /// Max value for the finders fee
     pub max_finders_fee_percent: Decimal,
-    /// Min value for a bid
+    /// Min value for asks and bids, unless set per asset in `ASSET_PARAMS`
     pub min_price: Uint128,
     /// Duration after expiry when a bid becomes stale
     pub stale_bid_duration: Duration,
     /// Stale bid removal reward
     pub bid_removal_reward_percent: Decimal,
-    /// Listing fee to reduce spam
+    /// Listing fee to reduce spam, unless set per asset in `ASSET_PARAMS`
     pub listing_fee: Uint128,
+    /// Bids on an auction landing within this many seconds of its end extend the auction
+    pub auction_extension_window: u64,
+    /// Seconds an auction is extended by, counted from the time of the late bid
+    pub auction_extension_duration: u64,
+    /// Min amount a bid on an auction has to beat the highest bid by
+    pub min_bid_increment_percent: Decimal,
+    /// Where listing fees are sent once they are no longer refundable
+    pub listing_fee_destination: FeeDestination,
+    /// Seconds after listing during which a cancelled ask gets its listing fee back
+    pub listing_fee_grace_period: u64,
+    /// Max total share of a sale taken by royalties, whether registered in `ROYALTIES`
+    /// or set by an sg721 or cw2981 collection
+    pub max_royalty_percent: Decimal,
+}
+
+/// Destination of fees collected by the marketplace
+#[cw_serde]
+pub enum FeeDestination {
+    Burn,
+    CommunityPool,
+    FeeCollector { address: Addr },
+}
+
+impl FeeDestination {
+    pub fn validate(&self, api: &dyn Api) -> StdResult<()> {
+        if let FeeDestination::FeeCollector { address } = self {
+            api.addr_validate(address.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FeeDestination {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FeeDestination::Burn => write!(f, "burn"),
+            FeeDestination::CommunityPool => write!(f, "community_pool"),
+            FeeDestination::FeeCollector { address } => write!(f, "{}", address),
+        }
+    }
 }
 
 pub const SUDO_PARAMS: Item<SudoParams> = Item::new("sudo-params");
 
+/// Default denom set at instantiation, used for listing fees and native token prices
+pub const DENOM: Item<String> = Item::new("denom");
+
+/// Kind of asset a denom refers to, CW20 tokens use their contract address as denom
+#[cw_serde]
+pub enum AssetType {
+    Native,
+    Cw20,
+}
+
+impl fmt::Display for AssetType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AssetType::Native => write!(f, "native"),
+            AssetType::Cw20 => write!(f, "cw20"),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct PaymentAsset {
+    pub asset_type: AssetType,
+    /// Disabled assets can no longer be used for new orders
+    pub enabled: bool,
+}
+
+/// Assets accepted for asks and bids, keyed by denom.
+/// Removed assets stay registered as disabled so open orders can still be paid out and refunded.
+pub const PAYMENT_ASSETS: Map<&str, PaymentAsset> = Map::new("payment-assets");
+
+/// Parameters for a single payment asset, overriding the global ones in `SudoParams`
+#[cw_serde]
+pub struct AssetParams {
+    /// Min value for asks and bids
+    pub min_price: Uint128,
+    /// Listing fee to reduce spam, always zero for CW20 assets as their asks pay the
+    /// global listing fee in the default denom
+    pub listing_fee: Uint128,
+    /// Overrides the global trading fee if set
+    pub trading_fee_percent: Option<Decimal>,
+}
+
+impl AssetParams {
+    /// Parameters of an asset, falling back to the global ones if no entry exists
+    pub fn load(store: &dyn Storage, denom: &str) -> StdResult<AssetParams> {
+        if let Some(params) = ASSET_PARAMS.may_load(store, denom)? {
+            return Ok(params);
+        }
+        let params = SUDO_PARAMS.load(store)?;
+        Ok(AssetParams {
+            min_price: params.min_price,
+            listing_fee: params.listing_fee,
+            trading_fee_percent: None,
+        })
+    }
+}
+
+pub const ASSET_PARAMS: Map<&str, AssetParams> = Map::new("asset-params");
+
 pub const ASK_HOOKS: Hooks = Hooks::new("ask-hooks");
 pub const BID_HOOKS: Hooks = Hooks::new("bid-hooks");
 pub const SALE_HOOKS: Hooks = Hooks::new("sale-hooks");
+pub const COLLECTION_BID_HOOKS: Hooks = Hooks::new("collection-bid-hooks");
 
 pub trait Order {
     fn expires_at(&self) -> Timestamp;
@@ -52,6 +153,7 @@ pub trait Order {
 pub enum SaleType {
     FixedPrice,
     Auction,
+    DutchAuction,
 }
 
 impl fmt::Display for SaleType {
@@ -59,7 +161,33 @@ impl fmt::Display for SaleType {
         match *self {
             SaleType::FixedPrice => write!(f, "fixed_price"),
             SaleType::Auction => write!(f, "auction"),
+            SaleType::DutchAuction => write!(f, "dutch_auction"),
+        }
+    }
+}
+
+/// Price schedule of a Dutch auction, declining linearly from `start_price`
+/// at `start_time` to `end_price` at `end_time`
+#[cw_serde]
+pub struct DutchAuction {
+    pub start_price: Uint128,
+    pub end_price: Uint128,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+}
+
+impl DutchAuction {
+    pub fn current_price(&self, now: Timestamp) -> Uint128 {
+        if now <= self.start_time {
+            return self.start_price;
         }
+        if now >= self.end_time {
+            return self.end_price;
+        }
+        let elapsed = now.seconds() - self.start_time.seconds();
+        let duration = self.end_time.seconds() - self.start_time.seconds();
+        let drop = (self.start_price - self.end_price).multiply_ratio(elapsed, duration);
+        self.start_price - drop
     }
 }
 
@@ -70,17 +198,32 @@ pub struct Ask {
     pub token_id: TokenId,
     pub seller: Addr,
     pub price: Uint128,
+    pub denom: String,
     pub funds_recipient: Option<Addr>,
     pub reserve_for: Option<Addr>,
     pub finders_fee_bps: Option<u64>,
     pub expires_at: Timestamp,
     pub is_active: bool,
+    /// Price schedule, only set for `SaleType::DutchAuction`
+    pub dutch_auction: Option<DutchAuction>,
+    /// Overrides the global min bid increment for this auction
+    pub min_bid_increment_percent: Option<Decimal>,
 }
 impl Order for Ask {
     fn expires_at(&self) -> Timestamp {
         self.expires_at
     }
 }
+
+impl Ask {
+    /// Price a buyer has to pay at `now`
+    pub fn current_price(&self, now: Timestamp) -> Uint128 {
+        match &self.dutch_auction {
+            Some(auction) => auction.current_price(now),
+            None => self.price,
+        }
+    }
+}
 /// Primary key for asks: (collection, token_id)
 pub type AskKey = (Addr, TokenId);
 /// Convenience ask key constructor
@@ -88,9 +231,50 @@ pub fn ask_key(collection: &Addr, token_id: TokenId) -> AskKey {
     (collection.clone(), token_id)
 }
 
+/// Share of the network fee paid to a recipient
+#[cw_serde]
+pub struct FeeShare {
+    pub recipient: Addr,
+    pub share_bps: u64,
+}
+
+/// How the network fee of a sale is split, shares and burn add up to 10000 bps
+#[cw_serde]
+pub struct FeeDistribution {
+    pub shares: Vec<FeeShare>,
+    pub burn_bps: u64,
+}
+
+pub const FEE_DISTRIBUTION: Item<FeeDistribution> = Item::new("fee-distribution");
+
+#[cw_serde]
+pub struct RoyaltyShare {
+    pub recipient: Addr,
+    pub share: Decimal,
+}
+
+/// Royalties registered by collection creators, used instead of the sg721 royalty
+pub const ROYALTIES: Map<&Addr, Vec<RoyaltyShare>> = Map::new("royalties");
+
+/// Listing fee paid for an ask, held by the contract during the grace period
+#[cw_serde]
+pub struct ListingFee {
+    pub fee: Coin,
+    pub paid_at: Timestamp,
+}
+
+/// Escrowed listing fees, released when the ask is removed or sold
+pub const LISTING_FEES: Map<AskKey, ListingFee> = Map::new("listing-fees");
+
+/// Reserve prices of auctions, kept apart from `Ask` so smart queries and hooks don't expose them.
+/// Contract storage is public, so the reserve can still be read with a raw query.
+pub const AUCTION_RESERVE_PRICES: Map<AskKey, Uint128> = Map::new("auction-reserve-prices");
+
 /// Defines indices for accessing Asks
 pub struct AskIndicies<'a> {
     pub collection: MultiIndex<'a, Addr, Ask, AskKey>,
+    // Keyed by the stored `price`, which is the start price of a Dutch auction.
+    // Its current price decays over time, so it can't be indexed.
     pub collection_price: MultiIndex<'a, (Addr, u128), Ask, AskKey>,
     pub seller: MultiIndex<'a, Addr, Ask, AskKey>,
 }
@@ -129,6 +313,7 @@ pub struct Bid {
     pub token_id: TokenId,
     pub bidder: Addr,
     pub price: Uint128,
+    pub denom: String,
     pub finders_fee_bps: Option<u64>,
     pub expires_at: Timestamp,
 }
@@ -138,7 +323,7 @@ impl Bid {
         collection: Addr,
         token_id: TokenId,
         bidder: Addr,
-        price: Uint128,
+        price: Coin,
         finders_fee_bps: Option<u64>,
         expires: Timestamp,
     ) -> Self {
@@ -146,7 +331,8 @@ impl Bid {
             collection,
             token_id,
             bidder,
-            price,
+            price: price.amount,
+            denom: price.denom,
             finders_fee_bps,
             expires_at: expires,
         }
@@ -219,6 +405,78 @@ pub fn bids<'a>() -> IndexedMap<'a, BidKey, Bid, BidIndicies<'a>> {
     IndexedMap::new("bids", indexes)
 }
 
+/// Represents a bid (offer) across an entire collection in the marketplace
+#[cw_serde]
+pub struct CollectionBid {
+    pub collection: Addr,
+    pub bidder: Addr,
+    pub price: Uint128,
+    pub denom: String,
+    pub finders_fee_bps: Option<u64>,
+    pub expires_at: Timestamp,
+}
+
+impl Order for CollectionBid {
+    fn expires_at(&self) -> Timestamp {
+        self.expires_at
+    }
+}
+
+/// Primary key for collection bids: (collection, bidder)
+pub type CollectionBidKey = (Addr, Addr);
+/// Convenience collection bid key constructor
+pub fn collection_bid_key(collection: &Addr, bidder: &Addr) -> CollectionBidKey {
+    (collection.clone(), bidder.clone())
+}
+
+/// Defines incides for accessing collection bids
+pub struct CollectionBidIndicies<'a> {
+    pub collection: MultiIndex<'a, Addr, CollectionBid, CollectionBidKey>,
+    pub collection_price: MultiIndex<'a, (Addr, u128), CollectionBid, CollectionBidKey>,
+    pub bidder: MultiIndex<'a, Addr, CollectionBid, CollectionBidKey>,
+    // Cannot include `Timestamp` in index, converted `Timestamp` to `seconds` and stored as `u64`
+    pub bidder_expires_at: MultiIndex<'a, (Addr, u64), CollectionBid, CollectionBidKey>,
+}
+
+impl<'a> IndexList<CollectionBid> for CollectionBidIndicies<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<CollectionBid>> + '_> {
+        let v: Vec<&dyn Index<CollectionBid>> = vec![
+            &self.collection,
+            &self.collection_price,
+            &self.bidder,
+            &self.bidder_expires_at,
+        ];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn collection_bids<'a>(
+) -> IndexedMap<'a, CollectionBidKey, CollectionBid, CollectionBidIndicies<'a>> {
+    let indexes = CollectionBidIndicies {
+        collection: MultiIndex::new(
+            |_pk: &[u8], d: &CollectionBid| d.collection.clone(),
+            "col_bids",
+            "col_bids__collection",
+        ),
+        collection_price: MultiIndex::new(
+            |_pk: &[u8], d: &CollectionBid| (d.collection.clone(), d.price.u128()),
+            "col_bids",
+            "col_bids__collection_price",
+        ),
+        bidder: MultiIndex::new(
+            |_pk: &[u8], d: &CollectionBid| d.bidder.clone(),
+            "col_bids",
+            "col_bids__bidder",
+        ),
+        bidder_expires_at: MultiIndex::new(
+            |_pk: &[u8], d: &CollectionBid| (d.bidder.clone(), d.expires_at.seconds()),
+            "col_bids",
+            "col_bids__bidder_expires_at",
+        ),
+    };
+    IndexedMap::new("col_bids", indexes)
+}
+
 #[cw_serde]
 pub struct TokenInfo {
     pub owner: Addr,
@@ -226,7 +484,29 @@ pub struct TokenInfo {
     pub token_uri: Option<String>,
     pub token_id: u64,
 }
-pub const TOKENS: Map<u64, TokenInfo> = Map::new("tokens");
+
+/// Defines indices for accessing natively minted tokens
+pub struct TokenIndicies<'a> {
+    pub owner: MultiIndex<'a, Addr, TokenInfo, u64>,
+}
+
+impl<'a> IndexList<TokenInfo> for TokenIndicies<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<TokenInfo>> + '_> {
+        let v: Vec<&dyn Index<TokenInfo>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn tokens<'a>() -> IndexedMap<'a, u64, TokenInfo, TokenIndicies<'a>> {
+    let indexes = TokenIndicies {
+        owner: MultiIndex::new(
+            |_pk: &[u8], d: &TokenInfo| d.owner.clone(),
+            "tokens",
+            "tokens__owner",
+        ),
+    };
+    IndexedMap::new("tokens", indexes)
+}
 
 #[cw_serde]
 pub struct State {
@@ -236,3 +516,9 @@ pub struct State {
     pub num_tokens: u64,
 }
 pub const CONFIG: Item<State> = Item::new("config");
+
+/// Voucher nonces that have already been redeemed, keyed by (creator pubkey, nonce)
+pub const USED_VOUCHER_NONCES: Map<(&[u8], u64), bool> = Map::new("used-voucher-nonces");
+
+/// Nonces of off-chain signed asks that were filled or cancelled, keyed by (seller, nonce)
+pub const SIGNED_ORDER_NONCES: Map<(&Addr, u64), bool> = Map::new("signed-order-nonces");
@@ -0,0 +1,453 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{Addr, Decimal, DepsMut, Env, Event, Uint128};
+use cw_utils::Duration;
+use sg_std::Response;
+
+use crate::contract::{MAX_AUCTION_EXTENSION, MAX_FEE_BPS};
+use crate::error::ContractError;
+use crate::helpers::{fee_distribution_validate, map_validate, ExpiryRange};
+use crate::msg::SudoMsg;
+use crate::state::{
+    AssetParams, AssetType, FeeDestination, FeeDistribution, PaymentAsset, ASK_HOOKS, ASSET_PARAMS,
+    BID_HOOKS, COLLECTION_BID_HOOKS, FEE_DISTRIBUTION, PAYMENT_ASSETS, SALE_HOOKS, SUDO_PARAMS,
+};
+
+pub struct ParamInfo {
+    trading_fee_bps: Option<u64>,
+    ask_expiry: Option<ExpiryRange>,
+    bid_expiry: Option<ExpiryRange>,
+    operators: Option<Vec<String>>,
+    max_finders_fee_bps: Option<u64>,
+    min_price: Option<Uint128>,
+    stale_bid_duration: Option<Duration>,
+    bid_removal_reward_bps: Option<u64>,
+    listing_fee: Option<Uint128>,
+    auction_extension_window: Option<u64>,
+    auction_extension_duration: Option<u64>,
+    min_bid_increment_bps: Option<u64>,
+    listing_fee_destination: Option<FeeDestination>,
+    listing_fee_grace_period: Option<u64>,
+    max_royalty_bps: Option<u64>,
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    let api = deps.api;
+
+    match msg {
+        SudoMsg::UpdateParams {
+            trading_fee_bps,
+            ask_expiry,
+            bid_expiry,
+            operators,
+            max_finders_fee_bps,
+            min_price,
+            stale_bid_duration,
+            bid_removal_reward_bps,
+            listing_fee,
+            auction_extension_window,
+            auction_extension_duration,
+            min_bid_increment_bps,
+            listing_fee_destination,
+            listing_fee_grace_period,
+            max_royalty_bps,
+        } => sudo_update_params(
+            deps,
+            env,
+            ParamInfo {
+                trading_fee_bps,
+                ask_expiry,
+                bid_expiry,
+                operators,
+                max_finders_fee_bps,
+                min_price,
+                stale_bid_duration,
+                bid_removal_reward_bps,
+                listing_fee,
+                auction_extension_window,
+                auction_extension_duration,
+                min_bid_increment_bps,
+                listing_fee_destination,
+                listing_fee_grace_period,
+                max_royalty_bps,
+            },
+        ),
+        SudoMsg::AddOperator { operator } => sudo_add_operator(deps, api.addr_validate(&operator)?),
+        SudoMsg::RemoveOperator { operator } => {
+            sudo_remove_operator(deps, api.addr_validate(&operator)?)
+        }
+        SudoMsg::AddAskHook { hook } => sudo_add_ask_hook(deps, api.addr_validate(&hook)?),
+        SudoMsg::RemoveAskHook { hook } => sudo_remove_ask_hook(deps, api.addr_validate(&hook)?),
+        SudoMsg::AddBidHook { hook } => sudo_add_bid_hook(deps, api.addr_validate(&hook)?),
+        SudoMsg::RemoveBidHook { hook } => sudo_remove_bid_hook(deps, api.addr_validate(&hook)?),
+        SudoMsg::AddSaleHook { hook } => sudo_add_sale_hook(deps, api.addr_validate(&hook)?),
+        SudoMsg::RemoveSaleHook { hook } => sudo_remove_sale_hook(deps, api.addr_validate(&hook)?),
+        SudoMsg::AddCollectionBidHook { hook } => {
+            sudo_add_collection_bid_hook(deps, api.addr_validate(&hook)?)
+        }
+        SudoMsg::RemoveCollectionBidHook { hook } => {
+            sudo_remove_collection_bid_hook(deps, api.addr_validate(&hook)?)
+        }
+        SudoMsg::AddPaymentAsset { denom, asset_type } => {
+            sudo_add_payment_asset(deps, denom, asset_type)
+        }
+        SudoMsg::RemovePaymentAsset { denom } => sudo_remove_payment_asset(deps, denom),
+        SudoMsg::SetAssetParams {
+            denom,
+            min_price,
+            listing_fee,
+            trading_fee_bps,
+        } => sudo_set_asset_params(deps, denom, min_price, listing_fee, trading_fee_bps),
+        SudoMsg::RemoveAssetParams { denom } => sudo_remove_asset_params(deps, denom),
+        SudoMsg::UpdateFeeDistribution { fee_distribution } => {
+            sudo_update_fee_distribution(deps, fee_distribution_validate(api, fee_distribution)?)
+        }
+    }
+}
+
+/// Only governance can update contract params
+pub fn sudo_update_params(
+    deps: DepsMut,
+    _env: Env,
+    param_info: ParamInfo,
+) -> Result<Response, ContractError> {
+    let ParamInfo {
+        trading_fee_bps,
+        ask_expiry,
+        bid_expiry,
+        operators,
+        max_finders_fee_bps,
+        min_price,
+        stale_bid_duration,
+        bid_removal_reward_bps,
+        listing_fee,
+        auction_extension_window,
+        auction_extension_duration,
+        min_bid_increment_bps,
+        listing_fee_destination,
+        listing_fee_grace_period,
+        max_royalty_bps,
+    } = param_info;
+
+    let mut params = SUDO_PARAMS.load(deps.storage)?;
+    let mut event = Event::new("update-params");
+
+    if let Some(trading_fee_bps) = trading_fee_bps {
+        if trading_fee_bps > MAX_FEE_BPS {
+            return Err(ContractError::InvalidTradingFeeBps(trading_fee_bps));
+        }
+        params.trading_fee_percent = Decimal::from_ratio(trading_fee_bps, MAX_FEE_BPS);
+        event = event.add_attribute("trading_fee_bps", trading_fee_bps.to_string());
+    }
+
+    if let Some(ask_expiry) = ask_expiry {
+        ask_expiry.validate()?;
+        event = event
+            .add_attribute("ask_expiry_min", ask_expiry.min.to_string())
+            .add_attribute("ask_expiry_max", ask_expiry.max.to_string());
+        params.ask_expiry = ask_expiry;
+    }
+
+    if let Some(bid_expiry) = bid_expiry {
+        bid_expiry.validate()?;
+        event = event
+            .add_attribute("bid_expiry_min", bid_expiry.min.to_string())
+            .add_attribute("bid_expiry_max", bid_expiry.max.to_string());
+        params.bid_expiry = bid_expiry;
+    }
+
+    if let Some(operators) = operators {
+        params.operators = map_validate(deps.api, &operators)?;
+        event = event.add_attribute("operators", operators.join(","));
+    }
+
+    if let Some(max_finders_fee_bps) = max_finders_fee_bps {
+        if max_finders_fee_bps > MAX_FEE_BPS {
+            return Err(ContractError::InvalidFindersFeeBps(max_finders_fee_bps));
+        }
+        params.max_finders_fee_percent = Decimal::from_ratio(max_finders_fee_bps, MAX_FEE_BPS);
+        event = event.add_attribute("max_finders_fee_bps", max_finders_fee_bps.to_string());
+    }
+
+    if let Some(min_price) = min_price {
+        params.min_price = min_price;
+        event = event.add_attribute("min_price", min_price.to_string());
+    }
+
+    if let Some(stale_bid_duration) = stale_bid_duration {
+        if !matches!(stale_bid_duration, Duration::Time(_)) {
+            return Err(ContractError::InvalidStaleBidDuration {});
+        }
+        params.stale_bid_duration = stale_bid_duration;
+        event = event.add_attribute("stale_bid_duration", stale_bid_duration.to_string());
+    }
+
+    if let Some(bid_removal_reward_bps) = bid_removal_reward_bps {
+        if bid_removal_reward_bps > MAX_FEE_BPS {
+            return Err(ContractError::InvalidBidRemovalRewardBps(
+                bid_removal_reward_bps,
+            ));
+        }
+        params.bid_removal_reward_percent =
+            Decimal::from_ratio(bid_removal_reward_bps, MAX_FEE_BPS);
+        event = event.add_attribute("bid_removal_reward_bps", bid_removal_reward_bps.to_string());
+    }
+
+    if let Some(listing_fee) = listing_fee {
+        params.listing_fee = listing_fee;
+        event = event.add_attribute("listing_fee", listing_fee.to_string());
+    }
+
+    if let Some(auction_extension_window) = auction_extension_window {
+        if auction_extension_window > MAX_AUCTION_EXTENSION {
+            return Err(ContractError::InvalidAuctionExtension(
+                auction_extension_window,
+            ));
+        }
+        params.auction_extension_window = auction_extension_window;
+        event = event.add_attribute(
+            "auction_extension_window",
+            auction_extension_window.to_string(),
+        );
+    }
+
+    if let Some(auction_extension_duration) = auction_extension_duration {
+        if auction_extension_duration > MAX_AUCTION_EXTENSION {
+            return Err(ContractError::InvalidAuctionExtension(
+                auction_extension_duration,
+            ));
+        }
+        params.auction_extension_duration = auction_extension_duration;
+        event = event.add_attribute(
+            "auction_extension_duration",
+            auction_extension_duration.to_string(),
+        );
+    }
+
+    if let Some(min_bid_increment_bps) = min_bid_increment_bps {
+        if min_bid_increment_bps > MAX_FEE_BPS {
+            return Err(ContractError::InvalidBidIncrementBps(min_bid_increment_bps));
+        }
+        params.min_bid_increment_percent = Decimal::from_ratio(min_bid_increment_bps, MAX_FEE_BPS);
+        event = event.add_attribute("min_bid_increment_bps", min_bid_increment_bps.to_string());
+    }
+
+    if let Some(listing_fee_destination) = listing_fee_destination {
+        listing_fee_destination.validate(deps.api)?;
+        event = event.add_attribute(
+            "listing_fee_destination",
+            listing_fee_destination.to_string(),
+        );
+        params.listing_fee_destination = listing_fee_destination;
+    }
+
+    if let Some(listing_fee_grace_period) = listing_fee_grace_period {
+        params.listing_fee_grace_period = listing_fee_grace_period;
+        event = event.add_attribute(
+            "listing_fee_grace_period",
+            listing_fee_grace_period.to_string(),
+        );
+    }
+
+    if let Some(max_royalty_bps) = max_royalty_bps {
+        if max_royalty_bps > MAX_FEE_BPS {
+            return Err(ContractError::InvalidRoyaltyBps(max_royalty_bps));
+        }
+        params.max_royalty_percent = Decimal::from_ratio(max_royalty_bps, MAX_FEE_BPS);
+        event = event.add_attribute("max_royalty_bps", max_royalty_bps.to_string());
+    }
+
+    SUDO_PARAMS.save(deps.storage, &params)?;
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_add_operator(deps: DepsMut, operator: Addr) -> Result<Response, ContractError> {
+    let mut params = SUDO_PARAMS.load(deps.storage)?;
+    if params.operators.contains(&operator) {
+        return Err(ContractError::OperatorAlreadyRegistered {});
+    }
+    params.operators.push(operator.clone());
+    SUDO_PARAMS.save(deps.storage, &params)?;
+
+    let event = Event::new("add-operator").add_attribute("operator", operator);
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_remove_operator(deps: DepsMut, operator: Addr) -> Result<Response, ContractError> {
+    let mut params = SUDO_PARAMS.load(deps.storage)?;
+    let index = params
+        .operators
+        .iter()
+        .position(|o| *o == operator)
+        .ok_or(ContractError::OperatorNotRegistered {})?;
+    params.operators.remove(index);
+    SUDO_PARAMS.save(deps.storage, &params)?;
+
+    let event = Event::new("remove-operator").add_attribute("operator", operator);
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_add_ask_hook(deps: DepsMut, hook: Addr) -> Result<Response, ContractError> {
+    ASK_HOOKS.add_hook(deps.storage, hook.clone())?;
+
+    let event = Event::new("add-ask-hook").add_attribute("hook", hook);
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_remove_ask_hook(deps: DepsMut, hook: Addr) -> Result<Response, ContractError> {
+    ASK_HOOKS.remove_hook(deps.storage, hook.clone())?;
+
+    let event = Event::new("remove-ask-hook").add_attribute("hook", hook);
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_add_bid_hook(deps: DepsMut, hook: Addr) -> Result<Response, ContractError> {
+    BID_HOOKS.add_hook(deps.storage, hook.clone())?;
+
+    let event = Event::new("add-bid-hook").add_attribute("hook", hook);
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_remove_bid_hook(deps: DepsMut, hook: Addr) -> Result<Response, ContractError> {
+    BID_HOOKS.remove_hook(deps.storage, hook.clone())?;
+
+    let event = Event::new("remove-bid-hook").add_attribute("hook", hook);
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_add_sale_hook(deps: DepsMut, hook: Addr) -> Result<Response, ContractError> {
+    SALE_HOOKS.add_hook(deps.storage, hook.clone())?;
+
+    let event = Event::new("add-sale-hook").add_attribute("hook", hook);
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_remove_sale_hook(deps: DepsMut, hook: Addr) -> Result<Response, ContractError> {
+    SALE_HOOKS.remove_hook(deps.storage, hook.clone())?;
+
+    let event = Event::new("remove-sale-hook").add_attribute("hook", hook);
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_add_collection_bid_hook(deps: DepsMut, hook: Addr) -> Result<Response, ContractError> {
+    COLLECTION_BID_HOOKS.add_hook(deps.storage, hook.clone())?;
+
+    let event = Event::new("add-collection-bid-hook").add_attribute("hook", hook);
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_remove_collection_bid_hook(
+    deps: DepsMut,
+    hook: Addr,
+) -> Result<Response, ContractError> {
+    COLLECTION_BID_HOOKS.remove_hook(deps.storage, hook.clone())?;
+
+    let event = Event::new("remove-collection-bid-hook").add_attribute("hook", hook);
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_add_payment_asset(
+    deps: DepsMut,
+    denom: String,
+    asset_type: AssetType,
+) -> Result<Response, ContractError> {
+    if denom.is_empty() {
+        return Err(ContractError::InvalidDenom(denom));
+    }
+    if asset_type == AssetType::Cw20 {
+        deps.api.addr_validate(&denom)?;
+    }
+    if let Some(asset) = PAYMENT_ASSETS.may_load(deps.storage, &denom)? {
+        if asset.asset_type != asset_type {
+            return Err(ContractError::PaymentAssetTypeMismatch {});
+        }
+    }
+    PAYMENT_ASSETS.save(
+        deps.storage,
+        &denom,
+        &PaymentAsset {
+            asset_type: asset_type.clone(),
+            enabled: true,
+        },
+    )?;
+
+    let event = Event::new("add-payment-asset")
+        .add_attribute("denom", denom)
+        .add_attribute("asset_type", asset_type.to_string());
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_remove_payment_asset(deps: DepsMut, denom: String) -> Result<Response, ContractError> {
+    let mut asset = PAYMENT_ASSETS
+        .may_load(deps.storage, &denom)?
+        .ok_or(ContractError::PaymentAssetNotRegistered {})?;
+    // kept as disabled so open orders in this asset can still be paid out and refunded
+    asset.enabled = false;
+    PAYMENT_ASSETS.save(deps.storage, &denom, &asset)?;
+
+    let event = Event::new("remove-payment-asset").add_attribute("denom", denom);
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_set_asset_params(
+    deps: DepsMut,
+    denom: String,
+    min_price: Uint128,
+    listing_fee: Uint128,
+    trading_fee_bps: Option<u64>,
+) -> Result<Response, ContractError> {
+    let asset = PAYMENT_ASSETS
+        .may_load(deps.storage, &denom)?
+        .ok_or(ContractError::PaymentAssetNotRegistered {})?;
+    // asks priced in cw20 tokens pay the global listing fee in the default denom
+    if asset.asset_type == AssetType::Cw20 && !listing_fee.is_zero() {
+        return Err(ContractError::InvalidListingFee(listing_fee));
+    }
+    let mut event = Event::new("set-asset-params")
+        .add_attribute("denom", denom.clone())
+        .add_attribute("min_price", min_price.to_string())
+        .add_attribute("listing_fee", listing_fee.to_string());
+    if let Some(trading_fee_bps) = trading_fee_bps {
+        if trading_fee_bps > MAX_FEE_BPS {
+            return Err(ContractError::InvalidTradingFeeBps(trading_fee_bps));
+        }
+        event = event.add_attribute("trading_fee_bps", trading_fee_bps.to_string());
+    }
+
+    let params = AssetParams {
+        min_price,
+        listing_fee,
+        trading_fee_percent: trading_fee_bps.map(|bps| Decimal::from_ratio(bps, MAX_FEE_BPS)),
+    };
+    ASSET_PARAMS.save(deps.storage, &denom, &params)?;
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_remove_asset_params(deps: DepsMut, denom: String) -> Result<Response, ContractError> {
+    if !ASSET_PARAMS.has(deps.storage, &denom) {
+        return Err(ContractError::AssetParamsNotFound(denom));
+    }
+    ASSET_PARAMS.remove(deps.storage, &denom);
+
+    let event = Event::new("remove-asset-params").add_attribute("denom", denom);
+    Ok(Response::new().add_event(event))
+}
+
+pub fn sudo_update_fee_distribution(
+    deps: DepsMut,
+    fee_distribution: FeeDistribution,
+) -> Result<Response, ContractError> {
+    FEE_DISTRIBUTION.save(deps.storage, &fee_distribution)?;
+
+    let mut event = Event::new("update-fee-distribution")
+        .add_attribute("burn_bps", fee_distribution.burn_bps.to_string());
+    for share in fee_distribution.shares {
+        event = event
+            .add_attribute("recipient", share.recipient)
+            .add_attribute("share_bps", share.share_bps.to_string());
+    }
+    Ok(Response::new().add_event(event))
+}